@@ -0,0 +1,112 @@
+//! Defines a clone-on-write image type.
+
+use core::{Image2D, Image2DMut, Image2DView, ImageBuffer2D, Pixel};
+
+use std::ops::Deref;
+
+/// An image which either owns its pixels or borrows them from another image. The borrowed pixels are only copied when
+/// the image is first mutated, which avoids copies in pipelines where most steps leave the image unchanged.
+///
+/// Read access goes through the `Image2D` trait object the image dereferences to.
+pub enum CowImage<'a, P>
+where
+    P: Pixel + 'a,
+{
+    /// Image owning its pixels.
+    Owned(ImageBuffer2D<P>),
+    /// Image borrowing its pixels.
+    Borrowed(Image2DView<'a, P>),
+}
+
+impl<'a, P> CowImage<'a, P>
+where
+    P: Pixel + 'a,
+{
+    /// Return `true` if the image owns its pixels.
+    pub fn is_owned(&self) -> bool {
+        match *self {
+            CowImage::Owned(_) => true,
+            CowImage::Borrowed(_) => false,
+        }
+    }
+
+    /// Return a mutable reference to the image, copying the borrowed pixels into owned storage first if needed.
+    pub fn to_mut(&mut self) -> &mut dyn Image2DMut<P> {
+        if let CowImage::Borrowed(ref view) = *self {
+            *self = CowImage::Owned(view.to_owned());
+        }
+        match *self {
+            CowImage::Owned(ref mut img) => img,
+            CowImage::Borrowed(_) => unreachable!(),
+        }
+    }
+
+    /// Return the owned image, copying the borrowed pixels if needed.
+    pub fn into_owned(self) -> ImageBuffer2D<P> {
+        match self {
+            CowImage::Owned(img) => img,
+            CowImage::Borrowed(view) => view.to_owned(),
+        }
+    }
+}
+
+impl<'a, P> Deref for CowImage<'a, P>
+where
+    P: Pixel + 'a,
+{
+    type Target = dyn Image2D<P> + 'a;
+
+    fn deref(&self) -> &(dyn Image2D<P> + 'a) {
+        match *self {
+            CowImage::Owned(ref img) => img,
+            CowImage::Borrowed(ref view) => view,
+        }
+    }
+}
+
+impl<'a, P> From<ImageBuffer2D<P>> for CowImage<'a, P>
+where
+    P: Pixel + 'a,
+{
+    fn from(img: ImageBuffer2D<P>) -> CowImage<'a, P> {
+        CowImage::Owned(img)
+    }
+}
+
+impl<'a, P> From<Image2DView<'a, P>> for CowImage<'a, P>
+where
+    P: Pixel + 'a,
+{
+    fn from(view: Image2DView<'a, P>) -> CowImage<'a, P> {
+        CowImage::Borrowed(view)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::{Luma, Rect};
+
+    #[test]
+    fn test_cow_image() {
+        let img = ImageBuffer2D::generate(6, 4, |(x, y)| Luma::new([(y * 6 + x) as u8]));
+        let mut cow = CowImage::from(img.get_view());
+        assert!(!cow.is_owned());
+        assert_eq!(cow.get_pixel(2, 1), &Luma::new([8]));
+        // Reads go through the borrowed pixels.
+        assert!(::std::ptr::eq(cow.get_pixel(0, 0), img.get_pixel(0, 0)));
+
+        cow.to_mut().put_pixel(2, 1, Luma::new([100]));
+        assert!(cow.is_owned());
+        assert_eq!(cow.get_pixel(2, 1), &Luma::new([100]));
+        assert_eq!(img.get_pixel(2, 1), &Luma::new([8]));
+
+        let sub = CowImage::from(img.sub_image(Rect::new(1, 1, 2, 2)));
+        assert_eq!(sub.dimensions(), (2, 2));
+        assert_eq!(
+            sub.into_owned(),
+            img.sub_image(Rect::new(1, 1, 2, 2)).to_owned()
+        );
+        assert!(CowImage::from(img).is_owned());
+    }
+}
@@ -1,9 +1,54 @@
 //! Definition of the dynamic image type.
 
-use core::{BitDepth, ImageBuffer2D, ImageType, Luma, LumaA, PixelType, Rgb, RgbA};
+use core::{
+    luma_alpha_to_luma, rgba_to_rgb, BitDepth, ChannelPixel, Image2D, ImageBuffer2D, ImageType,
+    Luma, LumaA, Pixel, PixelType, Rgb, RgbA,
+};
 
 use failure::Error;
 
+// Evaluate an expression on the image wrapped by any variant of a `DynamicImage`.
+macro_rules! dispatch {
+    ($dynamic:expr, $img:ident => $e:expr) => {
+        match $dynamic {
+            DynamicImage::LumaU8($img) => $e,
+            DynamicImage::LumaU16($img) => $e,
+            DynamicImage::LumaAU8($img) => $e,
+            DynamicImage::LumaAU16($img) => $e,
+            DynamicImage::RgbU8($img) => $e,
+            DynamicImage::RgbU16($img) => $e,
+            DynamicImage::RgbAU8($img) => $e,
+            DynamicImage::RgbAU16($img) => $e,
+        }
+    };
+}
+
+// Create a new image by converting each pixel of `img` with `f`.
+fn convert<P, Q, F>(img: &ImageBuffer2D<P>, mut f: F) -> ImageBuffer2D<Q>
+where
+    P: Pixel,
+    Q: Pixel,
+    F: FnMut(&P) -> Q,
+{
+    ImageBuffer2D::generate(img.width(), img.height(), |(x, y)| f(img.get_pixel(x, y)))
+}
+
+// Reduce the bit depth of a pixel from 16 to 8 bits, rounding to the nearest value.
+fn to_u8<const N: usize>(pixel: &ChannelPixel<u16, N>) -> ChannelPixel<u8, N> {
+    let mut out = ChannelPixel { data: [0u8; N] };
+    for (dst, src) in out.data.iter_mut().zip(pixel.data.iter()) {
+        *dst = ((u32::from(*src) + 128) / 257) as u8;
+    }
+    out
+}
+
+// Add an alpha channel to an 8 bit color pixel.
+fn with_alpha(pixel: &Rgb<u8>, alpha: u8) -> RgbA<u8> {
+    RgbA {
+        data: [pixel.data[0], pixel.data[1], pixel.data[2], alpha],
+    }
+}
+
 /// Image of dynamic pixel type.
 pub enum DynamicImage {
     /// 8 bit grayscale image.
@@ -86,6 +131,90 @@ impl DynamicImage {
         (self.channels(), self.bit_depth())
     }
 
+    /// Return the type of the image. Alias of `image_type`.
+    pub fn color_type(&self) -> ImageType {
+        self.image_type()
+    }
+
+    /// Return the image width.
+    pub fn width(&self) -> u32 {
+        dispatch!(self, img => img.width())
+    }
+
+    /// Return the image height.
+    pub fn height(&self) -> u32 {
+        dispatch!(self, img => img.height())
+    }
+
+    /// Return the image dimensions as a (width, height) tuple.
+    pub fn dimensions(&self) -> (u32, u32) {
+        dispatch!(self, img => img.dimensions())
+    }
+
+    /// Convert the image to an 8 bit color image, whatever its type. Grayscale values are replicated on each channel,
+    /// 16 bit values are rounded to 8 bits and the alpha channel is dropped.
+    pub fn to_rgb8(&self) -> ImageBuffer2D<Rgb<u8>> {
+        match self {
+            DynamicImage::LumaU8(img) => convert(img, |p| Rgb::from(p)),
+            DynamicImage::LumaU16(img) => convert(img, |p| Rgb::from(to_u8(p))),
+            DynamicImage::LumaAU8(img) => convert(img, |p| Rgb::from(Luma::from(p))),
+            DynamicImage::LumaAU16(img) => convert(img, |p| Rgb::from(Luma::from(to_u8(p)))),
+            DynamicImage::RgbU8(img) => Image2D::to_owned(&**img),
+            DynamicImage::RgbU16(img) => convert(img, to_u8),
+            DynamicImage::RgbAU8(img) => convert(img, |p| Rgb::from(p)),
+            DynamicImage::RgbAU16(img) => convert(img, |p| Rgb::from(to_u8(p))),
+        }
+    }
+
+    /// Convert the image to an 8 bit grayscale image, whatever its type. The luminance of color images is computed using
+    /// the ITU-R BT.601 weights, 16 bit values are rounded to 8 bits and the alpha channel is dropped.
+    pub fn to_luma8(&self) -> ImageBuffer2D<Luma<u8>> {
+        match self {
+            DynamicImage::LumaU8(img) => Image2D::to_owned(&**img),
+            DynamicImage::LumaU16(img) => convert(img, to_u8),
+            DynamicImage::LumaAU8(img) => luma_alpha_to_luma(&**img),
+            DynamicImage::LumaAU16(img) => convert(&luma_alpha_to_luma(&**img), to_u8),
+            DynamicImage::RgbU8(img) => convert(img, |p| Luma::from(p)),
+            DynamicImage::RgbU16(img) => convert(img, |p| to_u8(&Luma::from(p))),
+            DynamicImage::RgbAU8(img) => convert(&rgba_to_rgb(&**img), |p| Luma::from(p)),
+            DynamicImage::RgbAU16(img) => convert(&rgba_to_rgb(&**img), |p| to_u8(&Luma::from(p))),
+        }
+    }
+
+    /// Convert the image to a 16 bit grayscale image, whatever its type. The luminance of color images is computed using
+    /// the ITU-R BT.601 weights, 8 bit values are scaled to the full 16 bit range and the alpha channel is dropped.
+    pub fn to_luma16(&self) -> ImageBuffer2D<Luma<u16>> {
+        match self {
+            DynamicImage::LumaU16(img) => Image2D::to_owned(&**img),
+            DynamicImage::LumaAU16(img) => luma_alpha_to_luma(&**img),
+            DynamicImage::RgbU16(img) => convert(img, |p| Luma::from(p)),
+            DynamicImage::RgbAU16(img) => convert(&rgba_to_rgb(&**img), |p| Luma::from(p)),
+            _ => convert(&self.to_luma8(), |p| {
+                Luma::new([u16::from(p.data[0]) * 257])
+            }),
+        }
+    }
+
+    /// Convert the image to an 8 bit color image with alpha, whatever its type. Grayscale values are replicated on each
+    /// channel, 16 bit values are rounded to 8 bits and images without alpha channel are made opaque.
+    pub fn to_rgba8(&self) -> ImageBuffer2D<RgbA<u8>> {
+        match self {
+            DynamicImage::LumaU8(img) => convert(img, |p| with_alpha(&Rgb::from(p), 255)),
+            DynamicImage::LumaU16(img) => convert(img, |p| with_alpha(&Rgb::from(to_u8(p)), 255)),
+            DynamicImage::LumaAU8(img) => {
+                convert(img, |p| with_alpha(&Rgb::from(Luma::from(p)), p.data[1]))
+            }
+            DynamicImage::LumaAU16(img) => convert(img, |p| {
+                let p = to_u8(p);
+                with_alpha(&Rgb::from(Luma::from(p)), p.data[1])
+            }),
+            DynamicImage::RgbU8(img) => convert(img, |p| with_alpha(p, 255)),
+            DynamicImage::RgbU16(img) => convert(img, |p| with_alpha(&to_u8(p), 255)),
+            DynamicImage::RgbAU8(img) => Image2D::to_owned(&**img),
+            DynamicImage::RgbAU16(img) => convert(img, to_u8),
+        }
+    }
+
     /// Try extracting the image as an 8 bit grayscale image.
     pub fn into_luma_u8(self) -> Result<Box<ImageBuffer2D<Luma<u8>>>, Error> {
         match self {
@@ -150,3 +279,117 @@ impl DynamicImage {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dynamic_image_types() {
+        let l8 = ImageBuffer2D::generate(3, 2, |(x, _y)| Luma::new([x as u8 * 100]));
+        let l16 = ImageBuffer2D::generate(3, 2, |(x, _y)| Luma::new([x as u16 * 25700]));
+        let la8 = ImageBuffer2D::generate(3, 2, |(x, _y)| LumaA::new([x as u8 * 100, 7]));
+        let la16 = ImageBuffer2D::generate(3, 2, |(x, _y)| LumaA::new([x as u16 * 25700, 7]));
+        let rgb8 = ImageBuffer2D::generate(3, 2, |(x, y)| Rgb::new([x as u8, y as u8, 9]));
+        let rgb16 = ImageBuffer2D::generate(3, 2, |(x, y)| {
+            Rgb::new([x as u16 * 257, y as u16 * 257, 2313])
+        });
+        let rgba8 = ImageBuffer2D::generate(3, 2, |(x, y)| RgbA::new([x as u8, y as u8, 9, 0]));
+        let rgba16 = ImageBuffer2D::generate(3, 2, |(x, y)| {
+            RgbA::new([x as u16 * 257, y as u16 * 257, 2313, 0])
+        });
+
+        let images = vec![
+            (
+                DynamicImage::LumaU8(Box::new(l8)),
+                (PixelType::Luma, BitDepth::_8),
+            ),
+            (
+                DynamicImage::LumaU16(Box::new(l16)),
+                (PixelType::Luma, BitDepth::_16),
+            ),
+            (
+                DynamicImage::LumaAU8(Box::new(la8)),
+                (PixelType::LumaA, BitDepth::_8),
+            ),
+            (
+                DynamicImage::LumaAU16(Box::new(la16)),
+                (PixelType::LumaA, BitDepth::_16),
+            ),
+            (
+                DynamicImage::RgbU8(Box::new(rgb8.to_owned())),
+                (PixelType::Rgb, BitDepth::_8),
+            ),
+            (
+                DynamicImage::RgbU16(Box::new(rgb16)),
+                (PixelType::Rgb, BitDepth::_16),
+            ),
+            (
+                DynamicImage::RgbAU8(Box::new(rgba8)),
+                (PixelType::RgbA, BitDepth::_8),
+            ),
+            (
+                DynamicImage::RgbAU16(Box::new(rgba16)),
+                (PixelType::RgbA, BitDepth::_16),
+            ),
+        ];
+        let gray = ImageBuffer2D::generate(3, 2, |(x, _y)| Rgb::new([x as u8 * 100; 3]));
+        for (img, image_type) in images {
+            assert_eq!(img.color_type(), image_type);
+            assert_eq!((img.width(), img.height()), (3, 2));
+            let expected = if img.is_rgb() || img.is_rgb_alpha() {
+                &rgb8
+            } else {
+                &gray
+            };
+            assert_eq!(&img.to_rgb8(), expected);
+        }
+    }
+
+    #[test]
+    fn test_dynamic_image_conversions() {
+        let rgb =
+            ImageBuffer2D::generate(4, 3, |(x, y)| Rgb::new([x as u8 * 60, y as u8 * 100, 30]));
+        let dynamic = DynamicImage::RgbU8(Box::new(rgb.to_owned()));
+        let luma = dynamic.to_luma8();
+        for (l, c) in luma.iter().zip(rgb.iter()) {
+            assert_eq!(l, &Luma::from(c));
+        }
+        let rgba = dynamic.to_rgba8();
+        assert_eq!(rgba.get_pixel(2, 1), &RgbA::new([120, 100, 30, 255]));
+        assert_eq!(
+            dynamic.to_luma16().get_pixel(0, 0),
+            &Luma::new([luma.get_pixel(0, 0).data[0] as u16 * 257])
+        );
+
+        let l16 = ImageBuffer2D::generate(3, 1, |(x, _y)| {
+            Luma::new([[0u16, 25700, 65535][x as usize]])
+        });
+        let dynamic = DynamicImage::LumaU16(Box::new(l16.to_owned()));
+        let expected =
+            ImageBuffer2D::generate(3, 1, |(x, _y)| Luma::new([[0u8, 100, 255][x as usize]]));
+        assert_eq!(dynamic.to_luma8(), expected);
+        assert_eq!(dynamic.to_luma16(), l16);
+        assert_eq!(
+            dynamic.to_rgba8().get_pixel(1, 0),
+            &RgbA::new([100, 100, 100, 255])
+        );
+
+        let la = ImageBuffer2D::generate(1, 1, |_| LumaA::new([40u8, 128]));
+        let dynamic = DynamicImage::LumaAU8(Box::new(la));
+        assert_eq!(
+            dynamic.to_rgba8().get_pixel(0, 0),
+            &RgbA::new([40, 40, 40, 128])
+        );
+        assert_eq!(dynamic.to_luma16().get_pixel(0, 0), &Luma::new([40 * 257]));
+
+        let rgba = ImageBuffer2D::generate(1, 1, |_| RgbA::new([25700u16, 25700, 25700, 0]));
+        let dynamic = DynamicImage::RgbAU16(Box::new(rgba));
+        assert_eq!(dynamic.to_luma8().get_pixel(0, 0), &Luma::new([100]));
+        assert_eq!(dynamic.to_luma16().get_pixel(0, 0), &Luma::new([25700]));
+        assert_eq!(
+            dynamic.to_rgba8().get_pixel(0, 0),
+            &RgbA::new([100, 100, 100, 0])
+        );
+    }
+}
@@ -1,22 +1,29 @@
 //! Defines a generic 2D image type.
 #![allow(unknown_lints)]
 
-use core::{Luma, LumaA, Pixel, PixelType, Primitive, Rect, Rgb, RgbA};
+use core::font::{glyph, GLYPH_SIZE};
+use core::padding::{BorderMode, BorderedImage};
+use core::{ByteRepr, Luma, LumaA, Pixel, PixelType, Primitive, Rect, Rgb, RgbA};
+use helper::generic::round_saturate;
 
 use failure::Error;
 use ndarray;
 use ndarray::prelude::*;
 use ndarray::{OwnedRepr, ViewRepr};
-use num_traits::Zero;
+#[cfg(feature = "rand_integration")]
+use num_traits::{cast::cast, Bounded};
+use num_traits::{Float, Zero};
 #[cfg(feature = "rand_integration")]
 use rand::{
-    distributions::{Distribution, Standard},
-    Rng,
+    distributions::{Distribution, Normal, Standard},
+    rngs::StdRng,
+    Rng, SeedableRng,
 };
 
 use std::cmp::min;
+use std::fmt;
 use std::iter::{DoubleEndedIterator, ExactSizeIterator, IntoIterator};
-use std::ops::{Add, Div, Index, IndexMut, Mul, Rem, Sub};
+use std::ops::{Add, AddAssign, Div, Index, IndexMut, Mul, MulAssign, Rem, Sub, SubAssign};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 /// Bit depth of an image.
@@ -30,6 +37,23 @@ pub enum BitDepth {
 /// Type of an image.
 pub type ImageType = (PixelType, BitDepth);
 
+/// Memory layout of a buffer of pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryOrder {
+    /// Pixels are stored row by row, i.e. C order.
+    RowMajor,
+    /// Pixels are stored column by column, i.e. Fortran order.
+    ColMajor,
+}
+
+/// Width of an image, used to make the order of dimensions explicit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Width(pub u32);
+
+/// Height of an image, used to make the order of dimensions explicit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Height(pub u32);
+
 /// 2-dimensional image type.
 pub trait Image2D<P>: Sync
 where
@@ -59,12 +83,33 @@ where
     /// Return an iterator to the pixels and their indices. The type of the iterator is ((usize, usize), &P)
     fn enumerate_pixels(&self) -> ndarray::iter::IndexedIter<P, Ix2>;
 
+    /// Return an iterator over the coordinates `(x, y)` and values of the pixels in scanline order, the coordinates
+    /// being in the same order as the arguments of `get_pixel`.
+    fn enumerate_pixels_xy(&self) -> EnumeratePixelsXy<'_, P> {
+        EnumeratePixelsXy {
+            iter: self.enumerate_pixels(),
+        }
+    }
+
     /// Return an iterator over the pixels of an image row in left to right order.
     fn row(&self, y: u32) -> Option<RowIter<P>>;
 
     /// Return an iterator over the rows of an image in scanline order.
     fn rows(&self) -> RowsIter<P>;
 
+    /// Fold each row of the image in left to right order, starting from a copy of `init`, and return the results in
+    /// scanline order. This computes per row reductions, such as sums or extrema, without intermediate images.
+    fn scan_rows<T, F>(&self, init: T, mut f: F) -> Vec<T>
+    where
+        Self: Sized,
+        T: Clone,
+        F: FnMut(T, &P) -> T,
+    {
+        (0..self.height())
+            .map(|y| self.row(y).unwrap().fold(init.clone(), &mut f))
+            .collect()
+    }
+
     /// Return an iterator over the pixels of an image column in scanline order.
     fn col(&self, x: u32) -> Option<ColIter<P>>;
 
@@ -112,11 +157,99 @@ where
     /// Return an owned copy of the image.
     fn to_owned(&self) -> ImageBuffer2D<P>;
 
+    /// Return the image as an `ImageBuffer2D` if it is one, which gives access to the owned buffer behind a trait
+    /// object without copying it. Returns `None` for views.
+    fn as_image_buffer(&self) -> Option<&ImageBuffer2D<P>>;
+
+    /// Compare two images with floating point subpixels, returning true if their dimensions match and all their
+    /// corresponding subpixels differ by at most `tol`.
+    fn approx_eq(&self, other: &dyn Image2D<P>, tol: P::Subpixel) -> bool
+    where
+        P::Subpixel: Float,
+    {
+        self.dimensions() == other.dimensions()
+            && self.iter().zip(other.iter()).all(|(p, q)| {
+                p.channels()
+                    .iter()
+                    .zip(q.channels())
+                    .all(|(a, b)| (*a - *b).abs() <= tol)
+            })
+    }
+
+    /// Compute a 64-bit FNV-1a hash of the dimensions and of the little-endian bytes of the subpixels in scanline
+    /// order. The checksum only depends on the pixel values, not on the memory layout, and is stable across runs and
+    /// platforms, which makes it suitable for change detection and golden values in tests. It is not a cryptographic
+    /// hash.
+    fn checksum(&self) -> u64
+    where
+        P::Subpixel: ByteRepr,
+    {
+        const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+        const FNV_PRIME: u64 = 0x0100_0000_01b3;
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&self.width().to_le_bytes());
+        bytes.extend_from_slice(&self.height().to_le_bytes());
+        let mut hash = FNV_OFFSET;
+        let mut update = |bytes: &mut Vec<u8>| {
+            for &b in bytes.iter() {
+                hash = (hash ^ u64::from(b)).wrapping_mul(FNV_PRIME);
+            }
+            bytes.clear();
+        };
+        update(&mut bytes);
+        for p in self.iter() {
+            for c in p.channels() {
+                c.extend_le_bytes(&mut bytes);
+            }
+            update(&mut bytes);
+        }
+        hash
+    }
+
     /// Return a view over a rectangular region of the image.
     fn sub_image(&self, rect: Rect) -> Image2DView<P>;
+
+    /// Return a view of the image extended by `radius` pixels on each side according to `mode`, without copying it.
+    fn with_border(&self, radius: u32, mode: BorderMode) -> BorderedImage<'_, P> {
+        BorderedImage::new(self.get_view(), radius, mode)
+    }
+
+    /// Return an iterator over non-overlapping tiles of the image in scanline order. Tiles on the right and bottom
+    /// edges of the image are smaller if the image dimensions are not multiples of the tile dimensions.
+    ///
+    /// **Panics** if `tile_w` or `tile_h` is zero.
+    fn tiles(&self, tile_w: u32, tile_h: u32) -> TilesIter<'_, P> {
+        assert!(
+            tile_w != 0 && tile_h != 0,
+            "Tile dimensions must be strictly positive."
+        );
+        TilesIter {
+            view: self.get_view(),
+            tile_w,
+            tile_h,
+            x: 0,
+            y: 0,
+        }
+    }
+
+    /// Return an iterator over the coordinates `(x, y)` and values of the pixels for which `mask` is non-zero, in
+    /// scanline order.
+    ///
+    /// **Panics** if the dimensions of the mask differ from those of the image.
+    fn iter_masked<'a>(&'a self, mask: &'a dyn Image2D<Luma<u8>>) -> MaskedIter<'a, P> {
+        assert_eq!(
+            self.dimensions(),
+            mask.dimensions(),
+            "Mask dimensions do not match image dimensions."
+        );
+        MaskedIter {
+            pixels: self.enumerate_pixels(),
+            mask: mask.iter(),
+        }
+    }
 }
 
-impl<'a, P> IntoIterator for &'a Image2D<P>
+impl<'a, P> IntoIterator for &'a dyn Image2D<P>
 where
     P: Pixel + 'a,
 {
@@ -128,7 +261,7 @@ where
     }
 }
 
-impl<P> Index<(u32, u32)> for Image2D<P>
+impl<P> Index<(u32, u32)> for dyn Image2D<P>
 where
     P: Pixel,
 {
@@ -139,6 +272,42 @@ where
     }
 }
 
+/// Wrapper comparing images within a tolerance: two wrapped images are equal if their dimensions match and all their
+/// corresponding subpixels differ by at most the largest of both tolerances. This works for any subpixel type, and
+/// reads naturally in tests, e.g. `assert_eq!(Approx(&a, 1), Approx(&b, 1))`.
+pub struct Approx<'a, P>(pub &'a dyn Image2D<P>, pub P::Subpixel)
+where
+    P: Pixel;
+
+impl<'a, 'b, P> PartialEq<Approx<'b, P>> for Approx<'a, P>
+where
+    P: Pixel,
+{
+    fn eq(&self, other: &Approx<'b, P>) -> bool {
+        let tol = if self.1 > other.1 { self.1 } else { other.1 };
+        self.0.dimensions() == other.0.dimensions()
+            && self.0.iter().zip(other.0.iter()).all(|(p, q)| {
+                p.channels().iter().zip(q.channels()).all(|(&a, &b)| {
+                    let diff = if a > b { a - b } else { b - a };
+                    diff <= tol
+                })
+            })
+    }
+}
+
+impl<'a, P> fmt::Debug for Approx<'a, P>
+where
+    P: Pixel + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Approx")
+            .field("dimensions", &self.0.dimensions())
+            .field("tolerance", &self.1)
+            .field("pixels", &self.0.iter().collect::<Vec<&P>>())
+            .finish()
+    }
+}
+
 macro_rules! impl_image_op {
     ($op_name:ident, $op_fn:ident) => {
         // &T op &T impls
@@ -214,6 +383,25 @@ macro_rules! impl_image_op {
             }
         }
 
+        // T op T impl, reusing the buffer of the left operand
+        impl<P> $op_name<ImageBuffer2D<P>> for ImageBuffer2D<P>
+        where
+            P: Pixel + $op_name<P, Output = P>,
+        {
+            type Output = Result<ImageBuffer2D<P>, Error>;
+
+            fn $op_fn(mut self, rhs: ImageBuffer2D<P>) -> Self::Output {
+                let (dim_lhs, dim_rhs) = (self.dimensions(), rhs.dimensions());
+                if dim_lhs != dim_rhs {
+                    bail!("Image dimensions do not match");
+                }
+                for (lhs, rhs) in self.buffer.iter_mut().zip(rhs.buffer.iter()) {
+                    *lhs = lhs.clone().$op_fn(rhs.clone());
+                }
+                Ok(self)
+            }
+        }
+
         // Image2D impls
         impl<'a, 'b, P> $op_name<&'a dyn Image2D<P>> for &'b dyn Image2D<P>
         where
@@ -221,7 +409,7 @@ macro_rules! impl_image_op {
         {
             type Output = Result<ImageBuffer2D<P>, Error>;
 
-            fn $op_fn(self, rhs: &'a Image2D<P>) -> Self::Output {
+            fn $op_fn(self, rhs: &'a dyn Image2D<P>) -> Self::Output {
                 let (dim_lhs, dim_rhs) = (self.dimensions(), rhs.dimensions());
                 if dim_lhs != dim_rhs {
                     bail!("Image dimensions do not match");
@@ -238,6 +426,127 @@ impl_image_op!(Sub, sub);
 impl_image_op!(Div, div);
 impl_image_op!(Rem, rem);
 
+macro_rules! impl_scalar_op {
+    // Integer subpixels use saturating integer arithmetic, which is exact for every value of 64 bit types.
+    ($op_name:ident, $op_fn:ident, saturating $sat_fn:ident, $($t:ty),+) => {
+        $(
+        impl<'a, D, P> $op_name<$t> for &'a Image2DRepr<D, P>
+        where
+            P: Pixel<Subpixel = $t>,
+            D: PixelStorage<P>,
+        {
+            type Output = ImageBuffer2D<P>;
+
+            fn $op_fn(self, rhs: $t) -> Self::Output {
+                let mut out = self.to_owned();
+                for pix in out.iter_mut() {
+                    for c in pix.channels_mut() {
+                        *c = (*c).$sat_fn(rhs);
+                    }
+                }
+                out
+            }
+        }
+        )+
+    };
+    ($op_name:ident, $op_fn:ident, $($t:ty),+) => {
+        $(
+        // &T op scalar impl for floating point subpixels
+        impl<'a, D, P> $op_name<$t> for &'a Image2DRepr<D, P>
+        where
+            P: Pixel<Subpixel = $t>,
+            D: PixelStorage<P>,
+        {
+            type Output = ImageBuffer2D<P>;
+
+            fn $op_fn(self, rhs: $t) -> Self::Output {
+                let mut out = self.to_owned();
+                for pix in out.iter_mut() {
+                    for c in pix.channels_mut() {
+                        *c = (*c).$op_fn(rhs);
+                    }
+                }
+                out
+            }
+        }
+        )+
+    };
+}
+
+impl_scalar_op!(Add, add, saturating saturating_add, u8, u16, u32, u64, i8, i16, i32, i64);
+impl_scalar_op!(Mul, mul, saturating saturating_mul, u8, u16, u32, u64, i8, i16, i32, i64);
+impl_scalar_op!(Add, add, f32, f64);
+impl_scalar_op!(Mul, mul, f32, f64);
+
+macro_rules! impl_image_assign_op {
+    ($op_name:ident, $op_fn:ident, $assign_name:ident, $assign_fn:ident, $(#[$attr:meta])* $try_fn:ident) => {
+        // In place T op= &T impls. The operator traits cannot report errors, so these panic on dimension mismatch.
+        impl<'a, D, P, I> $assign_name<&'a I> for Image2DRepr<D, P>
+        where
+            P: Pixel + $op_name<P, Output = P>,
+            D: ndarray::DataMut<Elem = P> + PixelStorage<P>,
+            I: Image2D<P> + ?Sized,
+        {
+            fn $assign_fn(&mut self, rhs: &'a I) {
+                assert_eq!(
+                    self.dimensions(),
+                    rhs.dimensions(),
+                    "Image dimensions do not match"
+                );
+                for (lhs, rhs) in self.buffer.iter_mut().zip(rhs.iter()) {
+                    *lhs = lhs.clone().$op_fn(rhs.clone());
+                }
+            }
+        }
+
+        impl<D, P> Image2DRepr<D, P>
+        where
+            P: Pixel + $op_name<P, Output = P>,
+            D: ndarray::DataMut<Elem = P> + PixelStorage<P>,
+        {
+            $( #[$attr] )*
+            pub fn $try_fn(&mut self, rhs: &dyn Image2D<P>) -> Result<(), Error> {
+                if self.dimensions() != rhs.dimensions() {
+                    bail!("Image dimensions do not match");
+                }
+                self.$assign_fn(rhs);
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_image_assign_op!(
+    Add,
+    add,
+    AddAssign,
+    add_assign,
+    /// Add `rhs` to the image in place, pixel by pixel.
+    ///
+    /// **Error**: if the dimensions of both images do not match.
+    try_add_assign
+);
+impl_image_assign_op!(
+    Sub,
+    sub,
+    SubAssign,
+    sub_assign,
+    /// Subtract `rhs` from the image in place, pixel by pixel.
+    ///
+    /// **Error**: if the dimensions of both images do not match.
+    try_sub_assign
+);
+impl_image_assign_op!(
+    Mul,
+    mul,
+    MulAssign,
+    mul_assign,
+    /// Multiply the image by `rhs` in place, pixel by pixel.
+    ///
+    /// **Error**: if the dimensions of both images do not match.
+    try_mul_assign
+);
+
 /// Contains operations on mutable images.
 pub trait Image2DMut<P>: Image2D<P>
 where
@@ -257,12 +566,32 @@ where
     /// Return an iterator to the pixels and their indices. The type of the iterator is ((usize, usize), &mut P)
     fn enumerate_pixels_mut(&mut self) -> ndarray::iter::IndexedIterMut<P, Ix2>;
 
+    /// Return a mutable iterator over the coordinates `(x, y)` and values of the pixels in scanline order, the
+    /// coordinates being in the same order as the arguments of `get_pixel_mut`.
+    fn enumerate_pixels_xy_mut(&mut self) -> EnumeratePixelsXyMut<'_, P> {
+        EnumeratePixelsXyMut {
+            iter: self.enumerate_pixels_mut(),
+        }
+    }
+
     /// Return an iterator over the pixels of an image row in left to right order.
     fn row_mut(&mut self, y: u32) -> Option<RowIterMut<P>>;
 
     /// Return a mutable iterator over the rows of an image in scanline order.
     fn rows_mut(&mut self) -> RowsIterMut<P>;
 
+    /// Call `f` on each row of the image in scanline order, with the index of the row and a mutable iterator over its
+    /// pixels.
+    fn for_each_row_mut<F>(&mut self, mut f: F)
+    where
+        Self: Sized,
+        F: FnMut(u32, RowIterMut<P>),
+    {
+        for y in 0..self.height() {
+            f(y, self.row_mut(y).unwrap());
+        }
+    }
+
     /// Return an iterator over the pixels of an image column in scanline order.
     fn col_mut(&mut self, x: u32) -> Option<ColIterMut<P>>;
 
@@ -285,53 +614,160 @@ where
     }
 
     /// Blit (i.e. copy) a `Rect` from the source image onto the destination image.
-    fn blit_rect(&mut self, src_rect: Rect, dst_rect: Rect, img: &Image2D<P>) -> Result<(), Error>
+    fn blit_rect(
+        &mut self,
+        src_rect: Rect,
+        dst_rect: Rect,
+        img: &dyn Image2D<P>,
+    ) -> Result<(), Error>
     where
         Self: ::std::marker::Sized,
     {
-        if src_rect.size() != dst_rect.size() {
-            let (ws, hs) = src_rect.size();
-            let (wd, hd) = dst_rect.size();
-            bail!(
-                "Rects are not the same size. Source is ({}, {}), destination is ({}, {})",
-                ws,
-                hs,
-                wd,
-                hd
-            );
+        check_blit_rects(src_rect, dst_rect, img, self)?;
+        for (src_pixel, dst_pixel) in img.rect_iter(src_rect).zip(self.rect_iter_mut(dst_rect)) {
+            *dst_pixel = src_pixel.clone();
         }
+        Ok(())
+    }
 
-        if !src_rect.fits_image(img) {
-            bail!("Source rect does not fit source image.");
+    /// Fill the polygon described by `vertices` with the given value, using the even-odd rule. A pixel is considered
+    /// to be inside the polygon if its center is. The polygon is implicitly closed, and the parts of it falling out of
+    /// the image are clipped.
+    fn fill_polygon(&mut self, vertices: &[(i64, i64)], color: P) {
+        if vertices.len() < 3 {
+            return;
+        }
+        let (w, h) = self.dimensions();
+        let mut nodes = Vec::with_capacity(vertices.len());
+        for y in 0..h {
+            // Find the intersections of the polygon edges with the scanline going through the pixel centers.
+            let yc = f64::from(y) + 0.5;
+            nodes.clear();
+            for (i, &(x0, y0)) in vertices.iter().enumerate() {
+                let (x1, y1) = vertices[(i + 1) % vertices.len()];
+                let (fx0, fy0, fx1, fy1) = (x0 as f64, y0 as f64, x1 as f64, y1 as f64);
+                if (fy0 <= yc && fy1 > yc) || (fy1 <= yc && fy0 > yc) {
+                    nodes.push(fx0 + (yc - fy0) / (fy1 - fy0) * (fx1 - fx0));
+                }
+            }
+            nodes.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+            // Fill the pixels whose center lies between pairs of intersections.
+            for span in nodes.chunks(2).filter(|span| span.len() == 2) {
+                let start = (span[0] - 0.5).ceil().max(0.);
+                let end = (span[1] - 0.5).ceil().min(f64::from(w));
+                if start < end {
+                    let (start, end) = (start as u32, end as u32);
+                    self.fill_rect(Rect::new(start, y, end - start, 1), &color);
+                }
+            }
         }
-        if !dst_rect.fits_image(self) {
-            bail!("Source rect does not fit destination image.");
+    }
+
+    /// Draw `text` with its top left corner at the specified coordinates, using a built-in 8x8 bitmap font. Only the
+    /// set pixels of the glyphs are drawn, with the given color. A newline moves back to `x` on the next line of
+    /// text, and characters outside of the printable ASCII range are drawn as `'?'`. Glyphs falling off the image are
+    /// clipped.
+    fn draw_text(&mut self, text: &str, x: u32, y: u32, color: P) {
+        let (w, h) = self.dimensions();
+        let (mut gx, mut gy) = (u64::from(x), u64::from(y));
+        for c in text.chars() {
+            if c == '\n' {
+                gx = u64::from(x);
+                gy += u64::from(GLYPH_SIZE);
+                continue;
+            }
+            for (dy, bits) in glyph(c).iter().enumerate() {
+                let py = gy + dy as u64;
+                if py >= u64::from(h) {
+                    break;
+                }
+                for dx in 0..GLYPH_SIZE {
+                    let px = gx + u64::from(dx);
+                    if px < u64::from(w) && bits & (1 << dx) != 0 {
+                        self.put_pixel(px as u32, py as u32, color.clone());
+                    }
+                }
+            }
+            gx += u64::from(GLYPH_SIZE);
         }
+    }
 
-        for (src_pixel, dst_pixel) in img.rect_iter(src_rect).zip(self.rect_iter_mut(dst_rect)) {
-            *dst_pixel = src_pixel.clone();
+    /// Clamp each channel of every pixel to its own range: channel `i` is clamped to `[lo[i], hi[i]]`.
+    ///
+    /// Fails if `lo` or `hi` does not contain exactly one bound per channel.
+    fn clamp_channels(&mut self, lo: &[P::Subpixel], hi: &[P::Subpixel]) -> Result<(), Error> {
+        let n = P::N_CHANNELS as usize;
+        ensure!(
+            lo.len() == n && hi.len() == n,
+            "Expected {} bounds per channel, got {} lower and {} upper bounds",
+            n,
+            lo.len(),
+            hi.len()
+        );
+        for pixel in self.iter_mut() {
+            for ((c, l), h) in pixel.channels_mut().iter_mut().zip(lo).zip(hi) {
+                if *c < *l {
+                    *c = *l;
+                } else if *c > *h {
+                    *c = *h;
+                }
+            }
         }
         Ok(())
     }
 
+    /// Replace with `to` every pixel whose channels all differ from those of `from` by at most `tolerance`, e.g. to
+    /// key out a uniform background.
+    fn replace_color(&mut self, from: P, to: P, tolerance: P::Subpixel) {
+        for pixel in self.iter_mut() {
+            let matches = pixel
+                .channels()
+                .iter()
+                .zip(from.channels())
+                .all(|(&c, &f)| {
+                    let diff = if c > f { c - f } else { f - c };
+                    diff <= tolerance
+                });
+            if matches {
+                *pixel = to.clone();
+            }
+        }
+    }
+
     /// Return a mutable Iterator on the image pixels.
     fn iter_mut(&mut self) -> IterMut<P>;
 
     /// Return a mutable view on a rectangular region of the image.
     fn sub_image_mut(&mut self, rect: Rect) -> Image2DViewMut<P>;
+
+    /// Return a mutable view on a rectangular region of the image, checking that the region fits in the image.
+    ///
+    /// **Error**: if `rect` does not fit in the image.
+    fn sub_image_mut_checked(&mut self, rect: Rect) -> Result<Image2DViewMut<'_, P>, Error> {
+        ensure!(
+            u64::from(rect.left()) + u64::from(rect.width()) <= u64::from(self.width())
+                && u64::from(rect.top()) + u64::from(rect.height()) <= u64::from(self.height()),
+            "Rect {:?} does not fit in an image of dimensions {}x{}",
+            rect,
+            self.width(),
+            self.height()
+        );
+        Ok(self.sub_image_mut(rect))
+    }
 }
 
-impl<P> IndexMut<(u32, u32)> for Image2DMut<P>
+impl<P> IndexMut<(u32, u32)> for dyn Image2DMut<P>
 where
     P: Pixel,
-    Image2DMut<P>: Index<(u32, u32), Output = P>,
+    dyn Image2DMut<P>: Index<(u32, u32), Output = P>,
 {
     fn index_mut(&mut self, idx: (u32, u32)) -> &mut P {
         self.get_pixel_mut(idx.0, idx.1)
     }
 }
 
-impl<'a, P> IntoIterator for &'a mut Image2DMut<P>
+impl<'a, P> IntoIterator for &'a mut dyn Image2DMut<P>
 where
     P: Pixel + 'a,
 {
@@ -356,7 +792,7 @@ where
 impl<D, P> PartialEq for Image2DRepr<D, P>
 where
     P: Pixel,
-    D: ndarray::Data<Elem = P>,
+    D: PixelStorage<P>,
 {
     fn eq(&self, other: &Image2DRepr<D, P>) -> bool {
         self.dimensions() == other.dimensions() && self.iter().eq(other.iter())
@@ -373,7 +809,7 @@ where
 impl<D, P> Image2D<P> for Image2DRepr<D, P>
 where
     P: Pixel,
-    D: ndarray::Data<Elem = P>,
+    D: PixelStorage<P>,
 {
     fn width(&self) -> u32 {
         self.buffer.cols() as u32
@@ -453,6 +889,10 @@ where
         self.buffer.into_iter()
     }
 
+    fn as_image_buffer(&self) -> Option<&ImageBuffer2D<P>> {
+        D::as_image_buffer(self)
+    }
+
     fn to_owned(&self) -> ImageBuffer2D<P> {
         ImageBuffer2D {
             buffer: self.buffer.to_owned(),
@@ -472,19 +912,31 @@ where
 impl<D, P> Index<(u32, u32)> for Image2DRepr<D, P>
 where
     P: Pixel,
-    D: ndarray::Data<Elem = P>,
+    D: PixelStorage<P>,
 {
     type Output = P;
 
     fn index(&self, idx: (u32, u32)) -> &P {
-        self.get_pixel(idx.0, idx.1)
+        let (width, height) = self.dimensions();
+        match self.buffer.get([idx.1 as usize, idx.0 as usize]) {
+            Some(p) => p,
+            None => index_out_of_bounds(idx, width, height),
+        }
     }
 }
 
+// Panic with a message giving the coordinates and the dimensions of the image.
+fn index_out_of_bounds(idx: (u32, u32), width: u32, height: u32) -> ! {
+    panic!(
+        "Pixel index ({}, {}) out of bounds for an image of dimensions {}x{}",
+        idx.0, idx.1, width, height
+    )
+}
+
 impl<D, P> Image2DMut<P> for Image2DRepr<D, P>
 where
     P: Pixel,
-    D: ndarray::DataMut<Elem = P>,
+    D: ndarray::DataMut<Elem = P> + PixelStorage<P>,
 {
     fn get_pixel_mut(&mut self, x: u32, y: u32) -> &mut P {
         &mut self.buffer[[y as usize, x as usize]]
@@ -548,6 +1000,22 @@ where
         self.buffer.fill(value.clone());
     }
 
+    fn fill_rect(&mut self, rect: Rect, value: &P) {
+        let left = rect.left() as isize;
+        let top = rect.top() as isize;
+        let right = left + rect.width() as isize;
+        let bottom = top + rect.height() as isize;
+
+        let mut region = self.buffer.slice_mut(s![top..bottom, left..right]);
+        for mut row in region.genrows_mut() {
+            // Rows are contiguous in the row-major buffers of images, the fallback only guards against other layouts.
+            match row.as_slice_mut() {
+                Some(slice) => slice.fill(value.clone()),
+                None => row.fill(value.clone()),
+            }
+        }
+    }
+
     fn iter_mut(&mut self) -> IterMut<P> {
         self.buffer.iter_mut()
     }
@@ -565,18 +1033,22 @@ where
 impl<D, P> IndexMut<(u32, u32)> for Image2DRepr<D, P>
 where
     P: Pixel,
-    D: ndarray::DataMut<Elem = P>,
+    D: ndarray::DataMut<Elem = P> + PixelStorage<P>,
     Image2DRepr<D, P>: Index<(u32, u32), Output = P>,
 {
     fn index_mut(&mut self, idx: (u32, u32)) -> &mut P {
-        self.get_pixel_mut(idx.0, idx.1)
+        let (width, height) = self.dimensions();
+        match self.buffer.get_mut([idx.1 as usize, idx.0 as usize]) {
+            Some(p) => p,
+            None => index_out_of_bounds(idx, width, height),
+        }
     }
 }
 
 impl<'a, D, P> IntoIterator for &'a Image2DRepr<D, P>
 where
     P: Pixel + 'a,
-    D: ndarray::Data<Elem = P>,
+    D: PixelStorage<P>,
 {
     type Item = &'a P;
     type IntoIter = Iter<'a, P>;
@@ -589,7 +1061,7 @@ where
 impl<'a, D, P> IntoIterator for &'a mut Image2DRepr<D, P>
 where
     P: Pixel + 'a,
-    D: ndarray::DataMut<Elem = P>,
+    D: ndarray::DataMut<Elem = P> + PixelStorage<P>,
 {
     type Item = &'a mut P;
     type IntoIter = IterMut<'a, P>;
@@ -599,6 +1071,45 @@ where
     }
 }
 
+/// Storage of the pixels of an `Image2DRepr`, implemented for the owned and borrowed representations backing the image
+/// types of this crate.
+pub trait PixelStorage<P>: ndarray::Data<Elem = P>
+where
+    P: Pixel,
+{
+    /// Return the image itself if its pixels are owned, `None` otherwise.
+    fn as_image_buffer(img: &Image2DRepr<Self, P>) -> Option<&ImageBuffer2D<P>>
+    where
+        Self: Sized;
+}
+
+impl<P> PixelStorage<P> for ndarray::OwnedRepr<P>
+where
+    P: Pixel,
+{
+    fn as_image_buffer(img: &ImageBuffer2D<P>) -> Option<&ImageBuffer2D<P>> {
+        Some(img)
+    }
+}
+
+impl<'a, P> PixelStorage<P> for ndarray::ViewRepr<&'a P>
+where
+    P: Pixel,
+{
+    fn as_image_buffer<'b>(_img: &'b Image2DView<'a, P>) -> Option<&'b ImageBuffer2D<P>> {
+        None
+    }
+}
+
+impl<'a, P> PixelStorage<P> for ndarray::ViewRepr<&'a mut P>
+where
+    P: Pixel,
+{
+    fn as_image_buffer<'b>(_img: &'b Image2DViewMut<'a, P>) -> Option<&'b ImageBuffer2D<P>> {
+        None
+    }
+}
+
 /// Owned 2D image representation.
 pub type ImageBuffer2D<P> = Image2DRepr<ndarray::OwnedRepr<P>, P>;
 /// Borrowed 2D image representation.
@@ -624,6 +1135,27 @@ where
         }
     }
 
+    /// Create a new owned image of specified dimensions filled with zeros. Unlike `new`, the dimension types prevent
+    /// mixing up the width and height.
+    pub fn with_dims(width: Width, height: Height) -> ImageBuffer2D<P>
+    where
+        P: Pixel + Zero,
+    {
+        ImageBuffer2D::new(width.0, height.0)
+    }
+
+    /// Create an image from an `ndarray::Array2` without copying the pixel data. The array is indexed as
+    /// `(rows, cols)`, that is `(height, width)`.
+    pub fn from_array2(arr: Array2<P>) -> ImageBuffer2D<P> {
+        ImageBuffer2D { buffer: arr }
+    }
+
+    /// Consume self and return the underlying `ndarray::Array2` without copying the pixel data. The array is indexed
+    /// as `(rows, cols)`, that is `(height, width)`.
+    pub fn into_array2(self) -> Array2<P> {
+        self.buffer
+    }
+
     /// Consume self and return the raw underlying storage Vec.
     pub fn into_raw_vec(self) -> Vec<P> {
         self.buffer.into_raw_vec()
@@ -637,6 +1169,29 @@ where
         Ok(ImageBuffer2D { buffer: buf })
     }
 
+    /// Create a new image of specified dimensions from a `Vec` of the specified pixel type, stored in the given
+    /// memory order. Column-major data is transposed into the row-major layout used by images.
+    ///
+    /// **Error**: `InvalidDimensions` if the dimensions do not match the length of `v`.
+    pub fn from_vec_with_order(
+        w: u32,
+        h: u32,
+        v: Vec<P>,
+        order: MemoryOrder,
+    ) -> Result<ImageBuffer2D<P>, Error> {
+        match order {
+            MemoryOrder::RowMajor => ImageBuffer2D::from_vec(w, h, v),
+            MemoryOrder::ColMajor => {
+                let cols = Array2::from_shape_vec((w as usize, h as usize), v)?;
+                Ok(ImageBuffer2D {
+                    buffer: Array2::from_shape_fn((h as usize, w as usize), |(y, x)| {
+                        cols[[x, y]].clone()
+                    }),
+                })
+            }
+        }
+    }
+
     /// Create a new image of specified dimensions from a `Vec` of the specified pixel type's subpixel.
     ///
     /// **Error**: `InvalidDimensions` if the dimensions do not match the length of `v`.
@@ -669,53 +1224,341 @@ where
     }
 }
 
-#[cfg(feature = "rand_integration")]
-impl<P> ImageBuffer2D<P>
+impl<'a, P> Image2DView<'a, P>
 where
     P: Pixel,
 {
-    /// Generate a random image with the Standard distribution.
-    pub fn rand<R>(width: u32, height: u32, rng: &mut R) -> ImageBuffer2D<P>
-    where
-        R: Rng,
-        Standard: Distribution<P::Subpixel>,
-    {
-        ImageBuffer2D::generate(width, height, |(_x, _y)| P::rand(rng))
+    /// Create a view of specified dimensions borrowing a slice of pixels in row-major order, without copying them.
+    ///
+    /// **Error**: `InvalidDimensions` if the dimensions do not match the length of `data`.
+    pub fn from_slice(w: u32, h: u32, data: &'a [P]) -> Result<Image2DView<'a, P>, Error> {
+        ensure!(
+            data.len() == w as usize * h as usize,
+            "Buffer has incorrect size {}, expected {}.",
+            data.len(),
+            w as usize * h as usize
+        );
+        let buf = ArrayView2::from_shape((h as usize, w as usize), data)?;
+        Ok(Image2DView { buffer: buf })
     }
+}
 
-    /// Generate a random image with the given distribution
-    pub fn rand_with_distr<D, R>(
-        width: u32,
-        height: u32,
-        rng: &mut R,
-        distr: &D,
-    ) -> ImageBuffer2D<P>
-    where
-        R: Rng,
-        D: Distribution<P::Subpixel>,
-    {
-        ImageBuffer2D::generate(width, height, |(_x, _y)| P::rand_with_distr(rng, distr))
+impl ImageBuffer2D<Luma<u8>> {
+    /// Generate a smooth radial gradient going from `inner` at the center of the image to `outer` at its corners, which
+    /// can be used as a vignette mask.
+    pub fn radial_gradient(w: u32, h: u32, inner: u8, outer: u8) -> ImageBuffer2D<Luma<u8>> {
+        let (cx, cy) = ((w as f32 - 1.) / 2., (h as f32 - 1.) / 2.);
+        let max_dist = (cx * cx + cy * cy).sqrt();
+        let (inner, outer) = (f32::from(inner), f32::from(outer));
+        ImageBuffer2D::generate(w, h, |(x, y)| {
+            let (dx, dy) = (x as f32 - cx, y as f32 - cy);
+            let t = if max_dist > 0. {
+                (dx * dx + dy * dy).sqrt() / max_dist
+            } else {
+                0.
+            };
+            // Smoothstep falloff, flat at both the center and the corners.
+            let t = t * t * (3. - 2. * t);
+            Luma::new([round_saturate(inner + t * (outer - inner))])
+        })
     }
 }
 
-macro_rules! impl_iterators {
-    ( $( $(#[$attr:meta])* $name:ident: $t:ty;)+ ) => {
-        $(
-        $( #[$attr] )*
-        pub struct $name<'a, P>
-            where P: Pixel + 'a
-        {
-            iter: $t
-        }
+impl ImageBuffer2D<Luma<f32>> {
+    /// Generate a horizontal ramp, whose pixels are equal to their normalized x coordinate: 0 on the left column and 1
+    /// on the right one. Single column images are zero.
+    pub fn ramp_x(w: u32, h: u32) -> ImageBuffer2D<Luma<f32>> {
+        let scale = if w > 1 { 1. / (w - 1) as f32 } else { 0. };
+        ImageBuffer2D::generate(w, h, |(x, _)| Luma::new([x as f32 * scale]))
+    }
+
+    /// Generate a vertical ramp, whose pixels are equal to their normalized y coordinate: 0 on the top row and 1 on
+    /// the bottom one. Single row images are zero.
+    pub fn ramp_y(w: u32, h: u32) -> ImageBuffer2D<Luma<f32>> {
+        let scale = if h > 1 { 1. / (h - 1) as f32 } else { 0. };
+        ImageBuffer2D::generate(w, h, |(_, y)| Luma::new([y as f32 * scale]))
+    }
+
+    /// Generate an image whose pixels are equal to their distance to the center of the image, in pixels.
+    pub fn radius(w: u32, h: u32) -> ImageBuffer2D<Luma<f32>> {
+        let (cx, cy) = ((w as f32 - 1.) / 2., (h as f32 - 1.) / 2.);
+        ImageBuffer2D::generate(w, h, |(x, y)| {
+            let (dx, dy) = (x as f32 - cx, y as f32 - cy);
+            Luma::new([(dx * dx + dy * dy).sqrt()])
+        })
+    }
+
+    /// Generate an image of a single 2D gaussian of standard deviation `sigma` centered on `(cx, cy)`, whose peak value
+    /// is `amplitude`.
+    pub fn gaussian_spot(
+        w: u32,
+        h: u32,
+        cx: f32,
+        cy: f32,
+        sigma: f32,
+        amplitude: f32,
+    ) -> ImageBuffer2D<Luma<f32>> {
+        let sigma2_2 = 2. * sigma * sigma;
+        ImageBuffer2D::generate(w, h, |(x, y)| {
+            let (dx, dy) = (x as f32 - cx, y as f32 - cy);
+            Luma::new([amplitude * (-(dx * dx + dy * dy) / sigma2_2).exp()])
+        })
+    }
+
+    /// Generate an image which is zero everywhere except at `(x, y)`, where it is equal to `value`. Convolving an impulse
+    /// with a filter yields the impulse response of the filter.
+    ///
+    /// **Panics** if `(x, y)` is out of the image.
+    pub fn impulse(w: u32, h: u32, x: u32, y: u32, value: f32) -> ImageBuffer2D<Luma<f32>> {
+        let mut img = ImageBuffer2D::new(w, h);
+        img[(x, y)] = Luma::new([value]);
+        img
+    }
+}
 
-        impl<'a, P> Iterator for $name<'a, P>
-            where P: Pixel + 'a
+/// Exposure statistics of a grayscale 8-bit image. Created by `Image2DRepr`'s `exposure_stats` method.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExposureStats {
+    /// Fraction of the pixels at 0, i.e. of clipped shadows.
+    pub shadows: f32,
+    /// Fraction of the pixels at 255, i.e. of clipped highlights.
+    pub highlights: f32,
+    /// Difference between the largest and the smallest pixel values.
+    pub dynamic_range: u8,
+}
+
+impl<D> Image2DRepr<D, Luma<u8>>
+where
+    D: PixelStorage<Luma<u8>>,
+{
+    /// Compute the exposure statistics of the image, which help detecting under and over-exposure. All the statistics
+    /// are zero for an empty image.
+    pub fn exposure_stats(&self) -> ExposureStats {
+        let (mut shadows, mut highlights, mut count) = (0u64, 0u64, 0u64);
+        let (mut min, mut max) = (255u8, 0u8);
+        for p in self.iter() {
+            let v = p.data[0];
+            shadows += u64::from(v == 0);
+            highlights += u64::from(v == 255);
+            min = ::std::cmp::min(min, v);
+            max = ::std::cmp::max(max, v);
+            count += 1;
+        }
+        if count == 0 {
+            return ExposureStats {
+                shadows: 0.,
+                highlights: 0.,
+                dynamic_range: 0,
+            };
+        }
+        ExposureStats {
+            shadows: (shadows as f64 / count as f64) as f32,
+            highlights: (highlights as f64 / count as f64) as f32,
+            dynamic_range: max - min,
+        }
+    }
+}
+
+impl<D> Image2DRepr<D, RgbA<u8>>
+where
+    D: PixelStorage<RgbA<u8>>,
+{
+    /// Return a copy of the image with its color channels multiplied by the normalized alpha channel, as expected by
+    /// filtering and compositing operations.
+    pub fn premultiply_alpha(&self) -> ImageBuffer2D<RgbA<u8>> {
+        let mut out = self.to_owned();
+        for p in out.iter_mut() {
+            let a = u32::from(p.data[3]);
+            for c in &mut p.data[..3] {
+                *c = ((u32::from(*c) * a + 127) / 255) as u8;
+            }
+        }
+        out
+    }
+
+    /// Return a copy of a premultiplied image with its color channels divided by the normalized alpha channel, which
+    /// reverts `premultiply_alpha` up to rounding errors. Fully transparent pixels are set to transparent black.
+    pub fn unpremultiply_alpha(&self) -> ImageBuffer2D<RgbA<u8>> {
+        let mut out = self.to_owned();
+        for p in out.iter_mut() {
+            let a = u32::from(p.data[3]);
+            for c in &mut p.data[..3] {
+                *c = (u32::from(*c) * 255 + a / 2)
+                    .checked_div(a)
+                    .map_or(0, |v| min(v, 255) as u8);
+            }
+        }
+        out
+    }
+}
+
+impl<D> Image2DRepr<D, RgbA<u8>>
+where
+    D: ndarray::DataMut<Elem = RgbA<u8>> + PixelStorage<RgbA<u8>>,
+{
+    /// Blit a `Rect` from the source image onto the destination image like `blit_rect`, but alpha-blend the source
+    /// pixels over the destination ones with the "over" operator instead of overwriting them. Neither image is
+    /// expected to be premultiplied.
+    pub fn blit_rect_blended(
+        &mut self,
+        src_rect: Rect,
+        dst_rect: Rect,
+        img: &dyn Image2D<RgbA<u8>>,
+    ) -> Result<(), Error> {
+        check_blit_rects(src_rect, dst_rect, img, self)?;
+        for (src, dst) in img.rect_iter(src_rect).zip(self.rect_iter_mut(dst_rect)) {
+            let src_a = f32::from(src.data[3]) / 255.;
+            let dst_a = f32::from(dst.data[3]) / 255. * (1. - src_a);
+            let out_a = src_a + dst_a;
+            if out_a > 0. {
+                for c in 0..3 {
+                    let blended = f32::from(src.data[c]) * src_a + f32::from(dst.data[c]) * dst_a;
+                    dst.data[c] = round_saturate(blended / out_a);
+                }
+            }
+            dst.data[3] = round_saturate(out_a * 255.);
+        }
+        Ok(())
+    }
+}
+
+// Check that `src_rect` and `dst_rect` have the same size and fit their respective images, as required to blit.
+fn check_blit_rects<P, Q>(
+    src_rect: Rect,
+    dst_rect: Rect,
+    src: &dyn Image2D<P>,
+    dst: &dyn Image2D<Q>,
+) -> Result<(), Error>
+where
+    P: Pixel,
+    Q: Pixel,
+{
+    if src_rect.size() != dst_rect.size() {
+        let (ws, hs) = src_rect.size();
+        let (wd, hd) = dst_rect.size();
+        bail!(
+            "Rects are not the same size. Source is ({}, {}), destination is ({}, {})",
+            ws,
+            hs,
+            wd,
+            hd
+        );
+    }
+
+    if !src_rect.fits_image(src) {
+        bail!("Source rect does not fit source image.");
+    }
+    if !dst_rect.fits_image(dst) {
+        bail!("Source rect does not fit destination image.");
+    }
+    Ok(())
+}
+
+impl<P> Default for ImageBuffer2D<P>
+where
+    P: Pixel + Zero,
+{
+    /// Create an empty image, with dimensions `(0, 0)`.
+    fn default() -> ImageBuffer2D<P> {
+        ImageBuffer2D::new(0, 0)
+    }
+}
+
+#[cfg(feature = "rand_integration")]
+impl<P> ImageBuffer2D<P>
+where
+    P: Pixel,
+{
+    /// Generate a random image with the Standard distribution.
+    pub fn rand<R>(width: u32, height: u32, rng: &mut R) -> ImageBuffer2D<P>
+    where
+        R: Rng,
+        Standard: Distribution<P::Subpixel>,
+    {
+        ImageBuffer2D::generate(width, height, |(_x, _y)| P::rand(rng))
+    }
+
+    /// Generate a random image with the Standard distribution, using a random number generator seeded with `seed`.
+    /// Calls with the same seed generate the same image.
+    pub fn rand_seeded(width: u32, height: u32, seed: u64) -> ImageBuffer2D<P>
+    where
+        Standard: Distribution<P::Subpixel>,
+    {
+        ImageBuffer2D::rand(width, height, &mut StdRng::seed_from_u64(seed))
+    }
+
+    /// Generate a random image with the given distribution
+    pub fn rand_with_distr<D, R>(
+        width: u32,
+        height: u32,
+        rng: &mut R,
+        distr: &D,
+    ) -> ImageBuffer2D<P>
+    where
+        R: Rng,
+        D: Distribution<P::Subpixel>,
+    {
+        ImageBuffer2D::generate(width, height, |(_x, _y)| P::rand_with_distr(rng, distr))
+    }
+
+    /// Add gaussian noise of the given mean and standard deviation to each subpixel of the image. The result is
+    /// saturated to the range of the subpixel type.
+    pub fn add_gaussian_noise<R>(&mut self, mean: f32, stddev: f32, rng: &mut R)
+    where
+        R: Rng,
+    {
+        let distr = Normal::new(f64::from(mean), f64::from(stddev));
+        for pix in self.iter_mut() {
+            for c in pix.channels_mut() {
+                let noisy = cast::<P::Subpixel, f32>(*c).unwrap() + distr.sample(rng) as f32;
+                *c = round_saturate(noisy);
+            }
+        }
+    }
+
+    /// Add salt and pepper noise to the image: each pixel is replaced with probability `amount` by either the minimum
+    /// or the maximum value of the subpixel type, with equal probability.
+    pub fn add_salt_pepper_noise<R>(&mut self, amount: f32, rng: &mut R)
+    where
+        R: Rng,
+    {
+        for pix in self.iter_mut() {
+            if rng.gen::<f32>() < amount {
+                let value = if rng.gen::<bool>() {
+                    P::Subpixel::max_value()
+                } else {
+                    P::Subpixel::min_value()
+                };
+                for c in pix.channels_mut() {
+                    *c = value;
+                }
+            }
+        }
+    }
+}
+
+macro_rules! impl_iterators {
+    ( $( $(#[$attr:meta])* $name:ident: $t:ty;)+ ) => {
+        $(
+        $( #[$attr] )*
+        pub struct $name<'a, P>
+            where P: Pixel + 'a
+        {
+            iter: $t
+        }
+
+        impl<'a, P> Iterator for $name<'a, P>
+            where P: Pixel + 'a
         {
             type Item = <$t as Iterator>::Item;
 
             fn next(&mut self) -> Option<Self::Item> {
                 self.iter.next()
             }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.iter.size_hint()
+            }
         }
 
         impl<'a, P> ExactSizeIterator for $name<'a, P>
@@ -776,8 +1619,134 @@ impl_double_ended_iterators!(
     ColsIterMut: ndarray::iter::AxisIterMut<'a, P, Ix1>
 );
 
+/// Iterator over the pixels of an image selected by a mask. Created by `Image2D`'s `iter_masked` method.
+pub struct MaskedIter<'a, P>
+where
+    P: Pixel + 'a,
+{
+    pixels: ndarray::iter::IndexedIter<'a, P, Ix2>,
+    mask: Iter<'a, Luma<u8>>,
+}
+
+impl<'a, P> Iterator for MaskedIter<'a, P>
+where
+    P: Pixel + 'a,
+{
+    type Item = ((u32, u32), &'a P);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for ((y, x), p) in &mut self.pixels {
+            if self.mask.next()?.data[0] != 0 {
+                return Some(((x as u32, y as u32), p));
+            }
+        }
+        None
+    }
+}
+
+/// Iterator over the coordinates `(x, y)` and values of the pixels of an image. Created by `Image2D`'s
+/// `enumerate_pixels_xy` method.
+pub struct EnumeratePixelsXy<'a, P>
+where
+    P: Pixel + 'a,
+{
+    iter: ndarray::iter::IndexedIter<'a, P, Ix2>,
+}
+
+impl<'a, P> Iterator for EnumeratePixelsXy<'a, P>
+where
+    P: Pixel + 'a,
+{
+    type Item = ((u32, u32), &'a P);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter
+            .next()
+            .map(|((y, x), p)| ((x as u32, y as u32), p))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, P> ExactSizeIterator for EnumeratePixelsXy<'a, P> where P: Pixel + 'a {}
+
+/// Mutable iterator over the coordinates `(x, y)` and values of the pixels of an image. Created by `Image2DMut`'s
+/// `enumerate_pixels_xy_mut` method.
+pub struct EnumeratePixelsXyMut<'a, P>
+where
+    P: Pixel + 'a,
+{
+    iter: ndarray::iter::IndexedIterMut<'a, P, Ix2>,
+}
+
+impl<'a, P> Iterator for EnumeratePixelsXyMut<'a, P>
+where
+    P: Pixel + 'a,
+{
+    type Item = ((u32, u32), &'a mut P);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter
+            .next()
+            .map(|((y, x), p)| ((x as u32, y as u32), p))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, P> ExactSizeIterator for EnumeratePixelsXyMut<'a, P> where P: Pixel + 'a {}
+
+/// Iterator over the non-overlapping tiles of an image. Created by `Image2D`'s `tiles` method.
+pub struct TilesIter<'a, P>
+where
+    P: Pixel + 'a,
+{
+    view: Image2DView<'a, P>,
+    tile_w: u32,
+    tile_h: u32,
+    x: u32,
+    y: u32,
+}
+
+impl<'a, P> Iterator for TilesIter<'a, P>
+where
+    P: Pixel + 'a,
+{
+    type Item = (Rect, Image2DView<'a, P>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (w, h) = self.view.dimensions();
+        if self.x >= w {
+            self.x = 0;
+            self.y += self.tile_h;
+        }
+        if self.y >= h || w == 0 {
+            return None;
+        }
+
+        let rect = Rect::new(
+            self.x,
+            self.y,
+            min(self.tile_w, w - self.x),
+            min(self.tile_h, h - self.y),
+        );
+        self.x += self.tile_w;
+        let tile = Image2DRepr {
+            buffer: self.view.buffer.slice_move(s![
+                rect.top() as usize..(rect.bottom() + 1) as usize,
+                rect.left() as usize..(rect.right() + 1) as usize
+            ]),
+        };
+        Some((rect, tile))
+    }
+}
+
 /// Discard the alpha component of an `RgbA` image.
-pub fn rgba_to_rgb<P>(img: &Image2D<RgbA<P>>) -> ImageBuffer2D<Rgb<P>>
+pub fn rgba_to_rgb<P>(img: &dyn Image2D<RgbA<P>>) -> ImageBuffer2D<Rgb<P>>
 where
     P: Primitive,
 {
@@ -789,7 +1758,7 @@ where
 }
 
 /// Discard the alpha component of a `LumaA` image.
-pub fn luma_alpha_to_luma<P>(img: &Image2D<LumaA<P>>) -> ImageBuffer2D<Luma<P>>
+pub fn luma_alpha_to_luma<P>(img: &dyn Image2D<LumaA<P>>) -> ImageBuffer2D<Luma<P>>
 where
     P: Primitive,
 {
@@ -802,8 +1771,12 @@ where
 
 #[cfg(test)]
 mod tests {
-    use core::{Image2D, Image2DMut, ImageBuffer2D, Luma, Pixel, Rect, Region};
+    use core::{
+        Approx, ExposureStats, Height, Image2D, Image2DMut, Image2DView, ImageBuffer2D, Luma,
+        MemoryOrder, Pixel, Rect, Region, Rgb, RgbA, Width,
+    };
 
+    use ndarray::Array2;
     use num_traits::Zero;
     #[cfg(feature = "rand_integration")]
     use rand::thread_rng;
@@ -832,6 +1805,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_from_vec_with_order() {
+        let v: Vec<Luma<u8>> = (0u8..6u8).map(|n| Luma::new([n])).collect();
+        let row_major =
+            ImageBuffer2D::from_vec_with_order(3, 2, v.clone(), MemoryOrder::RowMajor).unwrap();
+        let col_major =
+            ImageBuffer2D::from_vec_with_order(2, 3, v.clone(), MemoryOrder::ColMajor).unwrap();
+        assert_eq!(row_major.dimensions(), (3, 2));
+        assert_eq!(col_major.dimensions(), (2, 3));
+        for y in 0..2 {
+            for x in 0..3 {
+                assert_eq!(row_major.get_pixel(x, y), col_major.get_pixel(y, x));
+            }
+        }
+        // Column-major images are still stored in standard order.
+        assert_eq!(
+            col_major.as_slice().unwrap(),
+            &[0, 3, 1, 4, 2, 5]
+                .iter()
+                .map(|&n| Luma::new([n]))
+                .collect::<Vec<_>>()[..]
+        );
+        assert!(ImageBuffer2D::from_vec_with_order(4, 2, v, MemoryOrder::ColMajor).is_err());
+    }
+
     #[test]
     fn test_new() {
         fn test_zeros_helper<P>(w: u32, h: u32)
@@ -848,6 +1846,84 @@ mod tests {
         test_zeros_helper::<Luma<f32>>(100, 200);
     }
 
+    #[test]
+    fn test_with_dims() {
+        let img = ImageBuffer2D::<Luma<u8>>::with_dims(Width(7), Height(3));
+        assert_eq!(img.dimensions(), (7, 3));
+        assert_eq!(img.rows().count(), 3);
+        assert!(img.iter().all(|p| p.is_zero()));
+    }
+
+    #[test]
+    fn test_radial_gradient() {
+        let img = ImageBuffer2D::radial_gradient(21, 15, 255, 10);
+        assert_eq!(img.dimensions(), (21, 15));
+        assert_eq!(img.get_pixel(10, 7), &Luma::new([255]));
+        for &(x, y) in &[(0, 0), (20, 0), (0, 14), (20, 14)] {
+            assert_eq!(img.get_pixel(x, y), &Luma::new([10]));
+        }
+        let radius = (10..21)
+            .map(|x| img.get_pixel(x, 7).data[0])
+            .collect::<Vec<u8>>();
+        for pair in radius.windows(2) {
+            assert!(pair[1] <= pair[0]);
+        }
+        assert!(radius[10] < radius[0]);
+        assert_eq!(
+            ImageBuffer2D::radial_gradient(1, 1, 200, 0).get_pixel(0, 0),
+            &Luma::new([200])
+        );
+    }
+
+    #[test]
+    fn test_default() {
+        let img = ImageBuffer2D::<Luma<u8>>::default();
+        assert_eq!(img.dimensions(), (0, 0));
+        assert_eq!(img.iter().count(), 0);
+        assert_eq!(img.rows().count(), 0);
+        assert_eq!(img.tiles(2, 2).count(), 0);
+
+        let mut img = ImageBuffer2D::<Luma<u8>>::new(3, 2);
+        let taken = ::std::mem::take(&mut img);
+        assert_eq!(taken.dimensions(), (3, 2));
+        assert_eq!(img.dimensions(), (0, 0));
+    }
+
+    #[test]
+    fn test_array2_conversions() {
+        let arr = Array2::from_shape_fn((2, 3), |(y, x)| Luma::new([(10 * y + x) as u8]));
+        let ptr = arr.as_ptr();
+        let img = ImageBuffer2D::from_array2(arr);
+        assert_eq!(img.dimensions(), (3, 2));
+        assert_eq!(img.get_pixel(2, 1), &Luma::new([12]));
+
+        let arr = img.into_array2();
+        assert_eq!(arr.as_ptr(), ptr);
+        assert_eq!(arr.dim(), (2, 3));
+        assert_eq!(arr[[1, 2]], Luma::new([12]));
+    }
+
+    #[test]
+    fn test_view_from_slice() {
+        let data = [
+            Luma::new([1u8]),
+            Luma::new([2]),
+            Luma::new([3]),
+            Luma::new([4]),
+            Luma::new([5]),
+            Luma::new([6]),
+        ];
+        let view = Image2DView::from_slice(3, 2, &data).unwrap();
+        assert_eq!(view.dimensions(), (3, 2));
+        assert_eq!(view.get_pixel(0, 0), &Luma::new([1]));
+        assert_eq!(view.get_pixel(2, 0), &Luma::new([3]));
+        assert_eq!(view.get_pixel(1, 1), &Luma::new([5]));
+        assert_eq!(view.as_slice().unwrap().as_ptr(), data.as_ptr());
+
+        assert!(Image2DView::from_slice(4, 2, &data).is_err());
+        assert!(Image2DView::from_slice(2, 2, &data).is_err());
+    }
+
     #[test]
     fn test_into_iter() {
         let v: Vec<Luma<u8>> = (1..10).map(Luma::from).collect();
@@ -879,6 +1955,134 @@ mod tests {
         let _ = &img1 + &img2;
     }
 
+    #[test]
+    fn test_ops_by_value() {
+        let v1 = [10, 11, 12, 13, 14, 15, 16, 17, 18];
+        let v2 = [8, 7, 6, 5, 4, 3, 2, 1, 1];
+        let img1 = ImageBuffer2D::<Luma<u8>>::from_raw_vec(3, 3, &v1).unwrap();
+        let img2 = ImageBuffer2D::<Luma<u8>>::from_raw_vec(3, 3, &v2).unwrap();
+        assert_eq!(
+            (img1.to_owned() + img2.to_owned()).unwrap(),
+            (&img1 + &img2).unwrap()
+        );
+        assert_eq!(
+            (img1.to_owned() - img2.to_owned()).unwrap(),
+            (&img1 - &img2).unwrap()
+        );
+        assert_eq!(
+            (img1.to_owned() * img2.to_owned()).unwrap(),
+            (&img1 * &img2).unwrap()
+        );
+        assert_eq!(
+            (img1.to_owned() / img2.to_owned()).unwrap(),
+            (&img1 / &img2).unwrap()
+        );
+        assert_eq!(
+            (img1.to_owned() % img2.to_owned()).unwrap(),
+            (&img1 % &img2).unwrap()
+        );
+        assert!((img1 + ImageBuffer2D::new(3, 2)).is_err());
+    }
+
+    #[test]
+    fn test_scalar_ops() {
+        let v = [0, 1, 2, 100, 127, 128, 200, 254, 255];
+        let img = ImageBuffer2D::<Luma<u8>>::from_raw_vec(3, 3, &v).unwrap();
+        let doubled = &img * 2;
+        for (p, q) in doubled.iter().zip(img.iter()) {
+            assert_eq!(p.data[0], q.data[0].saturating_mul(2));
+        }
+        let shifted = &img.get_view() + 100;
+        for (p, q) in shifted.iter().zip(img.iter()) {
+            assert_eq!(p.data[0], q.data[0].saturating_add(100));
+        }
+
+        let img = ImageBuffer2D::generate(2, 2, |(x, y)| Luma::new([x as f32 - y as f32]));
+        assert_eq!((&img * 0.5).get_pixel(1, 0), &Luma::new([0.5]));
+        assert_eq!((&img + -1.).get_pixel(0, 1), &Luma::new([-2.]));
+
+        // 64 bit integers are not rounded through floating point.
+        let big = (1u64 << 60) + 1;
+        let img = ImageBuffer2D::<Luma<u64>>::from_raw_vec(3, 1, &[big, 3, u64::MAX - 1]).unwrap();
+        assert_eq!(
+            (&img + 1).into_raw_vec(),
+            vec![Luma::new([big + 1]), Luma::new([4]), Luma::new([u64::MAX])]
+        );
+        assert_eq!(
+            (&img * 3).into_raw_vec(),
+            vec![Luma::new([3 * big]), Luma::new([9]), Luma::new([u64::MAX])]
+        );
+        let img = ImageBuffer2D::<Luma<i64>>::from_raw_vec(2, 1, &[i64::MIN + 1, (1i64 << 55) + 1])
+            .unwrap();
+        assert_eq!(
+            (&img * -1).into_raw_vec(),
+            vec![Luma::new([i64::MAX]), Luma::new([-(1i64 << 55) - 1])]
+        );
+        assert_eq!((&img + -2).get_pixel(0, 0), &Luma::new([i64::MIN]));
+    }
+
+    #[test]
+    fn test_approx_wrapper() {
+        let img = ImageBuffer2D::generate(6, 5, |(x, y)| Rgb::new([x as f32, y as f32, 0.5]));
+        let mut perturbed = img.to_owned();
+        perturbed.get_pixel_mut(2, 3).data[1] += 1e-4;
+        perturbed.get_pixel_mut(5, 0).data[2] -= 2e-4;
+        assert_ne!(img, perturbed);
+        assert_eq!(Approx(&img, 1e-3), Approx(&perturbed, 1e-3));
+        assert_ne!(Approx(&img, 1e-5), Approx(&perturbed, 1e-5));
+        // The largest tolerance is used.
+        assert_eq!(Approx(&img, 0.), Approx(&perturbed, 1e-3));
+        assert_ne!(Approx(&img, 1e3), Approx(&ImageBuffer2D::new(5, 6), 1e3));
+
+        let a = ImageBuffer2D::generate(4, 3, |(x, y)| Luma::new([(x * 10 + y) as u8]));
+        let b = ImageBuffer2D::generate(4, 3, |(x, y)| Luma::new([(x * 10 + y + x % 2) as u8]));
+        assert_eq!(Approx(&a, 1), Approx(&b, 1));
+        assert_eq!(Approx(&b, 1), Approx(&a.get_view(), 1));
+        assert_ne!(Approx(&a, 0), Approx(&b, 0));
+    }
+
+    #[test]
+    fn test_approx_eq() {
+        let img = ImageBuffer2D::generate(6, 5, |(x, y)| Rgb::new([x as f32, y as f32, 0.5]));
+        let mut perturbed = img.to_owned();
+        perturbed.get_pixel_mut(2, 3).data[1] += 1e-4;
+        perturbed.get_pixel_mut(5, 0).data[2] -= 2e-4;
+        assert!(img != perturbed);
+        assert!(img.approx_eq(&perturbed, 1e-3));
+        assert!(!img.approx_eq(&perturbed, 1e-5));
+        assert!(img.approx_eq(&img.get_view(), 0.));
+        assert!(!img.approx_eq(&ImageBuffer2D::new(5, 6), 1e3));
+    }
+
+    #[test]
+    fn test_add_assign() {
+        let v1 = [0, 1, 2, 3, 4, 5, 6, 7, 8];
+        let v2 = [8, 7, 6, 5, 4, 3, 2, 1, 0];
+        let img1 = ImageBuffer2D::<Luma<u8>>::from_raw_vec(3, 3, &v1).unwrap();
+        let img2 = ImageBuffer2D::<Luma<u8>>::from_raw_vec(3, 3, &v2).unwrap();
+        let sum = (&img1 + &img2).unwrap();
+
+        let mut acc = img1.to_owned();
+        acc.try_add_assign(&img2).unwrap();
+        assert_eq!(acc, sum);
+
+        let mut acc = img1.to_owned();
+        acc += &img2;
+        acc -= &img2.get_view();
+        assert_eq!(acc, img1);
+
+        let mut acc = img1.to_owned();
+        acc *= &img2 as &dyn Image2D<Luma<u8>>;
+        assert_eq!(acc, (&img1 * &img2).unwrap());
+
+        let small = ImageBuffer2D::<Luma<u8>>::new(2, 3);
+        let mut acc = img1.to_owned();
+        assert!(acc.try_add_assign(&small).is_err());
+        assert!(acc.try_sub_assign(&small).is_err());
+        assert!(acc.try_mul_assign(&small).is_err());
+        assert_eq!(acc, img1);
+    }
+
     #[test]
     fn test_row() {
         let v = [0, 1, 2, 3, 4, 5, 6, 7, 8];
@@ -973,6 +2177,219 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_for_each_row_mut() {
+        let mut img = ImageBuffer2D::<Luma<u8>>::new(4, 3);
+        img.for_each_row_mut(|y, row| {
+            for pix in row {
+                *pix = Luma::new([y as u8]);
+            }
+        });
+        let expected = ImageBuffer2D::generate(4, 3, |(_x, y)| Luma::new([y as u8]));
+        assert_eq!(img, expected);
+
+        let mut sub = img.sub_image_mut(Rect::new(1, 1, 2, 2));
+        sub.for_each_row_mut(|y, row| row.for_each(|pix| pix.data[0] += 10 * y as u8));
+        assert_eq!(img.get_pixel(1, 2), &Luma::new([12]));
+        assert_eq!(img.get_pixel(1, 1), &Luma::new([1]));
+    }
+
+    #[test]
+    fn test_draw_text() {
+        const GLYPH_A: [u8; 8] = [0x0C, 0x1E, 0x33, 0x33, 0x3F, 0x33, 0x33, 0x00];
+        let mut img = ImageBuffer2D::<Luma<u8>>::new(16, 12);
+        img.draw_text("A", 3, 2, Luma::new([255]));
+        for ((y, x), p) in img.enumerate_pixels() {
+            let (x, y) = (x as i64 - 3, y as i64 - 2);
+            let set =
+                (0..8).contains(&x) && (0..8).contains(&y) && GLYPH_A[y as usize] & (1 << x) != 0;
+            assert_eq!(p.data[0] == 255, set, "pixel ({}, {})", x + 3, y + 2);
+        }
+
+        // Glyphs falling off the image are clipped.
+        let mut img = ImageBuffer2D::<Luma<u8>>::new(16, 12);
+        img.draw_text("AA", 12, 8, Luma::new([255]));
+        assert_eq!(img.get_pixel(14, 8), &Luma::new([255]));
+        assert_eq!(img.get_pixel(13, 11), &Luma::new([255]));
+        assert_eq!(img.iter().filter(|p| p.data[0] == 255).count(), 9);
+    }
+
+    #[test]
+    fn test_clamp_channels() {
+        let mut img = ImageBuffer2D::generate(16, 16, |(x, y)| {
+            Rgb::new([(x * 16) as u8, (y * 16) as u8, ((x + y) * 8) as u8])
+        });
+        let original = img.to_owned();
+        img.clamp_channels(&[20, 0, 100], &[200, 50, 150]).unwrap();
+        for (p, q) in img.iter().zip(original.iter()) {
+            assert_eq!(p.data[0], q.data[0].clamp(20, 200));
+            assert_eq!(p.data[1], q.data[1].min(50));
+            assert_eq!(p.data[2], q.data[2].clamp(100, 150));
+        }
+
+        assert!(img.clamp_channels(&[0, 0], &[255, 255, 255]).is_err());
+        assert!(img.clamp_channels(&[0, 0, 0], &[255; 4]).is_err());
+    }
+
+    #[test]
+    fn test_premultiply_alpha() {
+        let img = ImageBuffer2D::generate(16, 16, |(x, y)| {
+            RgbA::new([(x * 17) as u8, (y * 17) as u8, 100, ((x + y) * 8) as u8])
+        });
+        let premultiplied = img.premultiply_alpha();
+        assert_eq!(
+            premultiplied.get_pixel(15, 15),
+            &RgbA::new([240, 240, 94, 240])
+        );
+        assert_eq!(
+            premultiplied.get_pixel(10, 5),
+            &RgbA::new([80, 40, 47, 120])
+        );
+        assert_eq!(premultiplied.get_pixel(0, 0), &RgbA::new([0, 0, 0, 0]));
+
+        // The round trip is exact for opaque pixels, and loses precision as alpha decreases.
+        let restored = premultiplied.unpremultiply_alpha();
+        for (p, q) in restored.iter().zip(img.iter()) {
+            let a = i32::from(q.data[3]);
+            assert_eq!(p.data[3], q.data[3]);
+            if a == 0 {
+                assert_eq!(p, &RgbA::new([0, 0, 0, 0]));
+                continue;
+            }
+            for (c, d) in p.data[..3].iter().zip(&q.data[..3]) {
+                assert!(
+                    (i32::from(*c) - i32::from(*d)).abs() <= 128 / a + 1,
+                    "{:?} {:?}",
+                    p,
+                    q
+                );
+            }
+        }
+
+        let opaque =
+            ImageBuffer2D::generate(8, 8, |(x, y)| RgbA::new([(x * 30) as u8, y as u8, 7, 255]));
+        assert_eq!(opaque.premultiply_alpha(), opaque);
+        assert_eq!(opaque.premultiply_alpha().unpremultiply_alpha(), opaque);
+    }
+
+    #[test]
+    fn test_iter_masked() {
+        let img = ImageBuffer2D::generate(6, 4, |(x, y)| Luma::new([(y * 6 + x) as u8]));
+        let mask = ImageBuffer2D::generate(6, 4, |(x, _y)| Luma::new([if x >= 3 { 1 } else { 0 }]));
+        let selected = img.iter_masked(&mask).collect::<Vec<_>>();
+        assert_eq!(selected.len(), 12);
+        for &((x, y), p) in &selected {
+            assert!(x >= 3);
+            assert_eq!(p, img.get_pixel(x, y));
+        }
+        assert_eq!(
+            selected[..4].iter().map(|s| s.0).collect::<Vec<_>>(),
+            vec![(3, 0), (4, 0), (5, 0), (3, 1)]
+        );
+
+        let sub = img.sub_image(Rect::new(1, 1, 3, 2));
+        let sub_mask = mask.sub_image(Rect::new(1, 1, 3, 2));
+        let selected = sub
+            .iter_masked(&sub_mask)
+            .map(|(_, p)| p.data[0])
+            .collect::<Vec<_>>();
+        assert_eq!(selected, vec![9, 15]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_iter_masked_dimensions() {
+        let img = ImageBuffer2D::<Luma<u8>>::new(6, 4);
+        img.iter_masked(&ImageBuffer2D::new(4, 6)).count();
+    }
+
+    #[test]
+    #[should_panic(expected = "Pixel index (3, 5) out of bounds for an image of dimensions 6x4")]
+    fn test_index_out_of_bounds() {
+        let img = ImageBuffer2D::<Luma<u8>>::new(6, 4);
+        let _ = img[(3, 5)];
+    }
+
+    #[test]
+    #[should_panic(expected = "Pixel index (6, 0) out of bounds for an image of dimensions 6x4")]
+    fn test_index_mut_out_of_bounds() {
+        let mut img = ImageBuffer2D::<Luma<u8>>::new(6, 4);
+        let mut view = img.sub_image_mut(Rect::new(0, 0, 6, 4));
+        view[(5, 3)] = Luma::new([1]);
+        view[(6, 0)] = Luma::new([1]);
+    }
+
+    #[test]
+    fn test_sub_image_mut_checked() {
+        let mut img = ImageBuffer2D::<Luma<u8>>::new(6, 4);
+        {
+            let mut view = img.sub_image_mut_checked(Rect::new(2, 1, 4, 3)).unwrap();
+            assert_eq!(view.dimensions(), (4, 3));
+            view.fill(&Luma::new([9]));
+        }
+        assert_eq!(img.iter().filter(|p| p.data[0] == 9).count(), 12);
+        assert_eq!(img.get_pixel(5, 3), &Luma::new([9]));
+        assert_eq!(img.get_pixel(1, 1), &Luma::new([0]));
+
+        assert!(img.sub_image_mut_checked(Rect::new(3, 1, 4, 3)).is_err());
+        assert!(img.sub_image_mut_checked(Rect::new(0, 2, 1, 3)).is_err());
+        assert!(img.sub_image_mut_checked(Rect::new(6, 0, 1, 1)).is_err());
+        // Rects whose far edge overflows a u32 are rejected as well.
+        assert!(img
+            .sub_image_mut_checked(Rect::new(u32::MAX, 0, 2, 1))
+            .is_err());
+        assert!(img
+            .sub_image_mut_checked(Rect::new(0, u32::MAX - 1, 1, 3))
+            .is_err());
+        let mut view = img.sub_image_mut(Rect::new(1, 1, 3, 2));
+        assert!(view.sub_image_mut_checked(Rect::new(0, 0, 3, 2)).is_ok());
+        assert!(view.sub_image_mut_checked(Rect::new(1, 0, 3, 2)).is_err());
+    }
+
+    #[test]
+    fn test_as_image_buffer() {
+        let mut img = ImageBuffer2D::generate(4, 3, |(x, y)| Luma::new([(x + y) as u8]));
+        {
+            let dyn_img: &dyn Image2D<Luma<u8>> = &img;
+            let buffer = dyn_img.as_image_buffer().unwrap();
+            assert!(::std::ptr::eq(buffer, &img));
+        }
+        assert!(img
+            .sub_image(Rect::new(0, 0, 2, 2))
+            .as_image_buffer()
+            .is_none());
+        assert!(img.get_view().as_image_buffer().is_none());
+        assert!(img
+            .sub_image_mut(Rect::new(1, 1, 2, 2))
+            .as_image_buffer()
+            .is_none());
+    }
+
+    #[test]
+    fn test_replace_color() {
+        let mut img = ImageBuffer2D::generate(8, 8, |(x, y)| {
+            if (2..6).contains(&x) && (2..6).contains(&y) {
+                Rgb::new([200, (x * 10) as u8, 30])
+            } else {
+                Rgb::new([10 + (x % 3) as u8, 250 - (y % 4) as u8, 5])
+            }
+        });
+        let original = img.to_owned();
+        img.replace_color(Rgb::new([10, 250, 5]), Rgb::new([0, 0, 0]), 3);
+        for ((y, x), p) in img.enumerate_pixels() {
+            if (2..6).contains(&x) && (2..6).contains(&y) {
+                assert_eq!(p, original.get_pixel(x as u32, y as u32));
+            } else {
+                assert_eq!(p, &Rgb::new([0, 0, 0]));
+            }
+        }
+
+        let mut img = original.to_owned();
+        img.replace_color(Rgb::new([10, 250, 5]), Rgb::new([0, 0, 0]), 1);
+        assert_eq!(img.get_pixel(2, 0), &Rgb::new([12, 250, 5]));
+        assert_eq!(img.get_pixel(1, 1), &Rgb::new([0, 0, 0]));
+    }
+
     #[test]
     fn test_col() {
         let v = [0, 1, 2, 3, 4, 5, 6, 7, 8];
@@ -1082,6 +2499,159 @@ mod tests {
         assert!(subimg_vec_eq(subimg1, &subimg1_vec));
     }
 
+    #[test]
+    fn test_enumerate_pixels_xy() {
+        let mut img = ImageBuffer2D::generate(5, 3, |(x, y)| Luma::new([(10 * x + y) as u8]));
+        let coords = img
+            .enumerate_pixels_xy()
+            .map(|(c, _)| c)
+            .collect::<Vec<_>>();
+        assert_eq!(coords.len(), 15);
+        assert_eq!(
+            &coords[..6],
+            &[(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (0, 1)]
+        );
+        for ((x, y), p) in img.enumerate_pixels_xy() {
+            assert_eq!(p, img.get_pixel(x, y));
+        }
+        let view = img.sub_image(Rect::new(1, 1, 3, 2));
+        assert_eq!(view.enumerate_pixels_xy().len(), 6);
+        for ((x, y), p) in view.enumerate_pixels_xy() {
+            assert_eq!(p, view.get_pixel(x, y));
+        }
+
+        for ((x, y), p) in img.enumerate_pixels_xy_mut() {
+            *p = Luma::new([(x + 100 * y) as u8]);
+        }
+        assert_eq!(img.get_pixel(4, 1), &Luma::new([104]));
+        assert_eq!(img.get_pixel(2, 2), &Luma::new([202]));
+    }
+
+    #[test]
+    fn test_checksum() {
+        let mut img = ImageBuffer2D::generate(4, 3, |(x, y)| Rgb::new([x as u8, y as u8, 7]));
+        assert_eq!(img.checksum(), 0x8fbe_32a3_b37d_4166);
+        let copy = img.to_owned();
+        assert_eq!(copy.checksum(), img.checksum());
+
+        img.put_pixel(3, 2, Rgb::new([3, 2, 8]));
+        assert_ne!(img.checksum(), copy.checksum());
+
+        // Only the pixel values matter, not the layout.
+        let sub = copy.sub_image(Rect::new(1, 1, 2, 2));
+        assert_eq!(sub.checksum(), sub.to_owned().checksum());
+        let flat = ImageBuffer2D::from_vec(2, 2, vec![Luma::new([0u16]); 4]).unwrap();
+        let row = ImageBuffer2D::from_vec(4, 1, vec![Luma::new([0u16]); 4]).unwrap();
+        assert_ne!(flat.checksum(), row.checksum());
+        assert_ne!(
+            flat.checksum(),
+            ImageBuffer2D::<Luma<u8>>::new(2, 2).checksum()
+        );
+    }
+
+    #[test]
+    fn test_ramps() {
+        let ramp = ImageBuffer2D::ramp_x(5, 3);
+        for y in 0..3 {
+            for x in 1..5 {
+                assert!(ramp.get_pixel(x, y).data[0] > ramp.get_pixel(x - 1, y).data[0]);
+                assert_eq!(ramp.get_pixel(x, y), ramp.get_pixel(x, 0));
+            }
+        }
+        assert_eq!(ramp.get_pixel(0, 2), &Luma::new([0.]));
+        assert_eq!(ramp.get_pixel(2, 1), &Luma::new([0.5]));
+        assert_eq!(ramp.get_pixel(4, 1), &Luma::new([1.]));
+
+        let ramp = ImageBuffer2D::ramp_y(2, 5);
+        assert_eq!(ramp.get_pixel(1, 1), &Luma::new([0.25]));
+        assert_eq!(ramp.get_pixel(0, 4), &Luma::new([1.]));
+        assert!(ImageBuffer2D::ramp_x(1, 4).iter().all(|p| p.data[0] == 0.));
+
+        let radius = ImageBuffer2D::radius(5, 3);
+        assert_eq!(radius.get_pixel(2, 1), &Luma::new([0.]));
+        assert_eq!(radius.get_pixel(0, 1), &Luma::new([2.]));
+        assert!((radius.get_pixel(4, 0).data[0] - 5f32.sqrt()).abs() < 1e-6);
+        assert_eq!(
+            ImageBuffer2D::radius(4, 4).get_pixel(1, 1),
+            ImageBuffer2D::radius(4, 4).get_pixel(2, 2)
+        );
+    }
+
+    #[test]
+    fn test_gaussian_spot_impulse() {
+        let spot = ImageBuffer2D::gaussian_spot(9, 7, 3., 4., 1.5, 2.);
+        assert_eq!(spot.get_pixel(3, 4), &Luma::new([2.]));
+        assert_eq!(spot.get_pixel(1, 4), spot.get_pixel(5, 4));
+        assert_eq!(spot.get_pixel(3, 2), spot.get_pixel(1, 4));
+        assert!((spot.get_pixel(4, 4).data[0] - 2. * (-1f32 / 4.5).exp()).abs() < 1e-6);
+        let max = spot.iter().map(|p| p.data[0]).fold(0., f32::max);
+        assert_eq!(max, 2.);
+
+        let impulse = ImageBuffer2D::impulse(5, 4, 1, 3, -7.);
+        assert_eq!(impulse.get_pixel(1, 3), &Luma::new([-7.]));
+        assert_eq!(impulse.iter().filter(|p| p.data[0] != 0.).count(), 1);
+    }
+
+    #[test]
+    fn test_exposure_stats() {
+        let mut img = ImageBuffer2D::generate(10, 8, |(x, _)| Luma::new([20 + 10 * x as u8]));
+        img.fill_rect(Rect::new(0, 0, 10, 2), &Luma::new([0]));
+        img.fill_rect(Rect::new(6, 6, 4, 2), &Luma::new([255]));
+        let stats = img.exposure_stats();
+        assert_eq!(stats.shadows, 0.25);
+        assert_eq!(stats.highlights, 0.1);
+        assert_eq!(stats.dynamic_range, 255);
+
+        let sub = img.sub_image(Rect::new(1, 2, 5, 4));
+        assert_eq!(
+            sub.exposure_stats(),
+            ExposureStats {
+                shadows: 0.,
+                highlights: 0.,
+                dynamic_range: 40
+            }
+        );
+        assert_eq!(
+            ImageBuffer2D::<Luma<u8>>::new(0, 3)
+                .exposure_stats()
+                .dynamic_range,
+            0
+        );
+    }
+
+    #[test]
+    fn test_scan_rows() {
+        let img = ImageBuffer2D::generate(5, 3, |(x, y)| Luma::new([((x + 2 * y) % 5 * 10) as u8]));
+        let max = img.scan_rows(0, |m, p| m.max(p.data[0]));
+        assert_eq!(max, vec![40, 40, 40]);
+        let first = img.scan_rows(None, |f, p| f.or(Some(p.data[0])));
+        assert_eq!(first, vec![Some(0), Some(20), Some(40)]);
+        let sums = img
+            .sub_image(Rect::new(1, 1, 2, 2))
+            .scan_rows(0u32, |s, p| s + u32::from(p.data[0]));
+        assert_eq!(sums, vec![70, 10]);
+        assert!(ImageBuffer2D::<Luma<u8>>::new(4, 0)
+            .scan_rows(0, |s, _| s)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_rect_iter_len() {
+        let mut img = ImageBuffer2D::<Luma<u8>>::new(5, 4);
+        let mut iter = img.rect_iter(Rect::new(1, 1, 3, 2));
+        for remaining in (0..=6).rev() {
+            assert_eq!(iter.len(), remaining);
+            assert_eq!(iter.size_hint(), (remaining, Some(remaining)));
+            assert_eq!(iter.next().is_some(), remaining > 0);
+        }
+
+        let mut iter = img.rect_iter_mut(Rect::new(0, 2, 5, 2));
+        assert_eq!(iter.size_hint(), (10, Some(10)));
+        iter.next();
+        assert_eq!(iter.len(), 9);
+        assert_eq!(img.row(1).unwrap().size_hint(), (5, Some(5)));
+    }
+
     #[test]
     fn test_translate_rect() {
         let img: ImageBuffer2D<Luma<u8>> = ImageBuffer2D::new(5, 5);
@@ -1109,6 +2679,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_fill_rect_views() {
+        let mut img = ImageBuffer2D::generate(9, 7, |(x, y)| Luma::new([(x + 10 * y) as u8]));
+        let expected = ImageBuffer2D::generate(9, 7, |(x, y)| {
+            if (3..7).contains(&x) && (2..5).contains(&y) {
+                Luma::new([200])
+            } else {
+                Luma::new([(x + 10 * y) as u8])
+            }
+        });
+        img.sub_image_mut(Rect::new(2, 1, 6, 5))
+            .fill_rect(Rect::new(1, 1, 4, 3), &Luma::new([200]));
+        assert_eq!(img, expected);
+    }
+
+    #[test]
+    fn test_fill_rect_large() {
+        let mut img = ImageBuffer2D::<Rgb<u8>>::new(2048, 2048);
+        let r = Rect::new(37, 11, 2000, 1900);
+        let start = ::std::time::Instant::now();
+        img.fill_rect(r, &Rgb::new([1, 2, 3]));
+        let elapsed = start.elapsed();
+        let filled = img.iter().filter(|p| p.data == [1, 2, 3]).count();
+        assert_eq!(filled, 2000 * 1900, "filled in {:?}", elapsed);
+        assert_eq!(img.get_pixel(36, 11), &Rgb::new([0, 0, 0]));
+        assert_eq!(img.get_pixel(2036, 1910), &Rgb::new([1, 2, 3]));
+        assert_eq!(img.get_pixel(2037, 1910), &Rgb::new([0, 0, 0]));
+    }
+
     #[test]
     fn test_blit_rect() {
         let mut img1 = ImageBuffer2D::<Luma<u8>>::new(64, 64);
@@ -1119,6 +2718,106 @@ mod tests {
         assert_eq!(img1, img2);
     }
 
+    #[test]
+    fn test_blit_rect_blended() {
+        let mut dst = ImageBuffer2D::generate(8, 8, |_| RgbA::new([0u8, 0, 200, 255]));
+        let src = ImageBuffer2D::generate(4, 4, |(x, _)| {
+            RgbA::new([255u8, 100, 0, if x < 2 { 128 } else { 255 }])
+        });
+        assert!(dst
+            .blit_rect_blended(Rect::new(0, 0, 4, 4), Rect::new(2, 3, 4, 4), &src)
+            .is_ok());
+        assert_eq!(dst.get_pixel(2, 3), &RgbA::new([128, 50, 100, 255]));
+        assert_eq!(dst.get_pixel(5, 6), &RgbA::new([255, 100, 0, 255]));
+        assert_eq!(dst.get_pixel(1, 3), &RgbA::new([0, 0, 200, 255]));
+
+        // Blending over a transparent destination keeps the source colors.
+        let mut clear = ImageBuffer2D::<RgbA<u8>>::new(4, 4);
+        assert!(clear
+            .blit_rect_blended(src.rect(), clear.rect(), &src)
+            .is_ok());
+        assert_eq!(clear, src);
+
+        assert!(dst
+            .blit_rect_blended(Rect::new(0, 0, 4, 4), Rect::new(0, 0, 3, 4), &src)
+            .is_err());
+        assert!(dst
+            .blit_rect_blended(Rect::new(0, 0, 4, 4), Rect::new(5, 5, 4, 4), &src)
+            .is_err());
+    }
+
+    #[test]
+    fn test_tiles() {
+        let img = ImageBuffer2D::generate(5, 5, |(x, y)| Luma::new([(x + 5 * y) as u8]));
+        let tiles = img.tiles(2, 2).collect::<Vec<_>>();
+        assert_eq!(tiles.len(), 9);
+
+        let sizes = tiles.iter().map(|(r, _)| r.size()).collect::<Vec<_>>();
+        assert_eq!(
+            sizes,
+            vec![
+                (2, 2),
+                (2, 2),
+                (1, 2),
+                (2, 2),
+                (2, 2),
+                (1, 2),
+                (2, 1),
+                (2, 1),
+                (1, 1),
+            ]
+        );
+        for (rect, tile) in &tiles {
+            assert_eq!(tile.dimensions(), rect.size());
+            for y in 0..rect.height() {
+                for x in 0..rect.width() {
+                    assert_eq!(
+                        tile.get_pixel(x, y),
+                        img.get_pixel(rect.left() + x, rect.top() + y)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_fill_polygon() {
+        // Crossing number test, evaluated at the pixel center.
+        fn inside(vertices: &[(i64, i64)], x: u32, y: u32) -> bool {
+            let (px, py) = (f64::from(x) + 0.5, f64::from(y) + 0.5);
+            let mut c = false;
+            for (i, &(x0, y0)) in vertices.iter().enumerate() {
+                let (x1, y1) = vertices[(i + 1) % vertices.len()];
+                let (x0, y0, x1, y1) = (x0 as f64, y0 as f64, x1 as f64, y1 as f64);
+                if (y0 > py) != (y1 > py) && px < (x1 - x0) * (py - y0) / (y1 - y0) + x0 {
+                    c = !c;
+                }
+            }
+            c
+        }
+
+        let triangle = [(2, 1), (17, 6), (5, 18)];
+        let mut img = ImageBuffer2D::<Luma<u8>>::new(20, 20);
+        img.fill_polygon(&triangle, Luma::new([255]));
+        let mut n_inside = 0;
+        for y in 0..20 {
+            for x in 0..20 {
+                if inside(&triangle, x, y) {
+                    assert_eq!(img.get_pixel(x, y), &Luma::new([255]));
+                    n_inside += 1;
+                } else {
+                    assert_eq!(img.get_pixel(x, y), &Luma::new([0]));
+                }
+            }
+        }
+        assert!(n_inside > 0);
+
+        // Polygons crossing the image borders are clipped.
+        let mut img = ImageBuffer2D::<Luma<u8>>::new(10, 10);
+        img.fill_polygon(&[(-5, -5), (20, -5), (20, 20), (-5, 20)], Luma::new([1]));
+        assert!(img.iter().all(|p| p == &Luma::new([1])));
+    }
+
     #[test]
     fn test_sub_image() {
         let img = ImageBuffer2D::generate(5, 5, |(x, y)| {
@@ -1234,4 +2933,63 @@ mod tests {
             .fold(0u32, |acc, p| acc + u32::from(p.data[0]));
         assert!(sum > 100_000_000 && sum < 130_000_000);
     }
+
+    #[test]
+    #[cfg(feature = "rand_integration")]
+    fn test_rand_seeded() {
+        let img1 = ImageBuffer2D::<Luma<u8>>::rand_seeded(64, 48, 42);
+        let img2 = ImageBuffer2D::<Luma<u8>>::rand_seeded(64, 48, 42);
+        let img3 = ImageBuffer2D::<Luma<u8>>::rand_seeded(64, 48, 43);
+        assert_eq!(img1, img2);
+        assert!(img1 != img3);
+    }
+
+    #[test]
+    #[cfg(feature = "rand_integration")]
+    fn test_add_gaussian_noise() {
+        fn stats(img: &ImageBuffer2D<Luma<f32>>) -> (f32, f32) {
+            let n = (img.width() * img.height()) as f32;
+            let mean = img.iter().fold(0., |acc, p| acc + p.data[0]) / n;
+            let var = img
+                .iter()
+                .fold(0., |acc, p| acc + (p.data[0] - mean) * (p.data[0] - mean))
+                / n;
+            (mean, var)
+        }
+
+        let mut img =
+            ImageBuffer2D::generate(500, 500, |(x, y)| Luma::new([((x + y) % 50) as f32]));
+        let (mean, var) = stats(&img);
+        img.add_gaussian_noise(3., 4., &mut thread_rng());
+        let (noisy_mean, noisy_var) = stats(&img);
+        assert!((noisy_mean - (mean + 3.)).abs() < 0.1);
+        assert!((noisy_var - (var + 16.)).abs() < 1.);
+
+        // Integer images saturate instead of wrapping around.
+        let mut img = ImageBuffer2D::generate(100, 100, |_| Luma::new([250u8]));
+        img.add_gaussian_noise(50., 1., &mut thread_rng());
+        assert!(img.iter().all(|p| p.data[0] == 255));
+    }
+
+    #[test]
+    #[cfg(feature = "rand_integration")]
+    fn test_add_salt_pepper_noise() {
+        let original =
+            ImageBuffer2D::generate(400, 250, |(x, y)| Luma::new([(100 + (x + y) % 50) as u8]));
+        let mut img = original.to_owned();
+        img.add_salt_pepper_noise(0.1, &mut thread_rng());
+
+        let (mut salt, mut pepper) = (0, 0);
+        for (p, o) in img.iter().zip(original.iter()) {
+            match p.data[0] {
+                0 => pepper += 1,
+                255 => salt += 1,
+                _ => assert_eq!(p, o),
+            }
+        }
+        let n = 400. * 250.;
+        assert!(((salt + pepper) as f32 / n - 0.1).abs() < 0.01);
+        assert!((salt as f32 / n - 0.05).abs() < 0.01);
+        assert!((pepper as f32 / n - 0.05).abs() < 0.01);
+    }
 }
@@ -1,6 +1,8 @@
 //! Contains the definitions of the core image types and traits.
 
+mod cow_image;
 mod dynamic_image;
+mod font;
 mod image2d;
 mod neighborhood;
 pub mod padding;
@@ -8,6 +10,7 @@ mod pixel_types;
 mod rect;
 mod traits;
 
+pub use self::cow_image::*;
 pub use self::dynamic_image::*;
 pub use self::image2d::*;
 pub use self::neighborhood::*;
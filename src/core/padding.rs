@@ -1,11 +1,11 @@
 //! Contains image padding functions.
 
-use core::{Image2D, Image2DMut, ImageBuffer2D, Pixel, Rect};
+use core::{Image2D, Image2DMut, Image2DView, ImageBuffer2D, Pixel, Rect};
 
 use num_traits::Zero;
 
 /// Pad an image with zeros.
-pub fn pad_zeros<P>(img: &Image2D<P>, radius: u32) -> ImageBuffer2D<P>
+pub fn pad_zeros<P>(img: &dyn Image2D<P>, radius: u32) -> ImageBuffer2D<P>
 where
     P: Pixel + Zero,
 {
@@ -17,7 +17,7 @@ where
 }
 
 /// Pad an image by replicating its borders.
-pub fn pad_replicate<P>(img: &Image2D<P>, radius: u32) -> ImageBuffer2D<P>
+pub fn pad_replicate<P>(img: &dyn Image2D<P>, radius: u32) -> ImageBuffer2D<P>
 where
     P: Pixel + Zero,
 {
@@ -86,7 +86,7 @@ where
 }
 
 /// Pad an image by wrapping around its borders.
-pub fn pad_wrap<P>(img: &Image2D<P>, radius: u32) -> ImageBuffer2D<P>
+pub fn pad_wrap<P>(img: &dyn Image2D<P>, radius: u32) -> ImageBuffer2D<P>
 where
     P: Pixel + Zero,
 {
@@ -134,7 +134,7 @@ where
 }
 
 /// Pad an image by mirroring its borders.
-pub fn pad_mirror<P>(img: &Image2D<P>, radius: u32) -> ImageBuffer2D<P>
+pub fn pad_mirror<P>(img: &dyn Image2D<P>, radius: u32) -> ImageBuffer2D<P>
 where
     P: Pixel + Zero,
 {
@@ -209,10 +209,111 @@ where
     padded
 }
 
+/// Strategy used to extend an image past its borders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BorderMode {
+    /// Pixels outside of the image are zero.
+    Zero,
+    /// Pixels outside of the image take the value of the closest border pixel.
+    Clamp,
+    /// The image is repeated periodically.
+    Wrap,
+    /// The image is mirrored across its borders, border pixels included.
+    Mirror,
+}
+
+/// Lightweight view of an image extended by `radius` pixels on each side according to a `BorderMode`, without
+/// allocating a padded buffer. Created by `Image2D`'s `with_border` method.
+pub struct BorderedImage<'a, P>
+where
+    P: Pixel + 'a,
+{
+    view: Image2DView<'a, P>,
+    radius: u32,
+    mode: BorderMode,
+    zero: P,
+}
+
+impl<'a, P> BorderedImage<'a, P>
+where
+    P: Pixel,
+{
+    /// Create a view of `view` extended by `radius` pixels on each side.
+    pub fn new(view: Image2DView<'a, P>, radius: u32, mode: BorderMode) -> BorderedImage<'a, P> {
+        BorderedImage {
+            view,
+            radius,
+            mode,
+            zero: P::zero_pixel(),
+        }
+    }
+
+    /// Return the number of pixels the image is extended by on each side.
+    pub fn radius(&self) -> u32 {
+        self.radius
+    }
+
+    /// Return the border mode used to extend the image.
+    pub fn mode(&self) -> BorderMode {
+        self.mode
+    }
+
+    /// Return the dimensions of the underlying image, without the border.
+    pub fn dimensions(&self) -> (u32, u32) {
+        self.view.dimensions()
+    }
+
+    /// Return a reference to the pixel at the given coordinates, which must lie in `[-radius, width + radius)` and
+    /// `[-radius, height + radius)`. Coordinates falling out of the image are mapped according to the border mode.
+    ///
+    /// **Panics** if the coordinates are out of the extended domain, or if the image is empty and the border mode is not
+    /// `Zero`.
+    pub fn get_pixel(&self, x: i64, y: i64) -> &P {
+        let (w, h) = self.view.dimensions();
+        let r = i64::from(self.radius);
+        assert!(
+            x >= -r && x < i64::from(w) + r && y >= -r && y < i64::from(h) + r,
+            "Coordinates ({}, {}) out of the extended image domain",
+            x,
+            y
+        );
+        match (
+            map_coordinate(x, w, self.mode),
+            map_coordinate(y, h, self.mode),
+        ) {
+            (Some(x), Some(y)) => self.view.get_pixel(x, y),
+            _ => &self.zero,
+        }
+    }
+}
+
+// Map a coordinate along an axis of length `len` into the image, or return `None` if it falls out of the image in
+// `Zero` mode.
+fn map_coordinate(c: i64, len: u32, mode: BorderMode) -> Option<u32> {
+    let len = i64::from(len);
+    if c >= 0 && c < len {
+        return Some(c as u32);
+    }
+    let mapped = match mode {
+        BorderMode::Zero => return None,
+        BorderMode::Clamp => c.max(0).min(len - 1),
+        BorderMode::Wrap => c.rem_euclid(len),
+        BorderMode::Mirror => {
+            let m = c.rem_euclid(2 * len);
+            if m < len {
+                m
+            } else {
+                2 * len - 1 - m
+            }
+        }
+    };
+    Some(mapped as u32)
+}
+
 #[cfg(test)]
 mod tests {
-    use core::padding::pad_zeros;
-    use core::{Image2D, Image2DMut, ImageBuffer2D, Luma};
+    use core::padding::{pad_mirror, pad_wrap, pad_zeros, BorderMode};
+    use core::{Image2D, Image2DMut, ImageBuffer2D, Luma, Rect};
 
     use num_traits::Zero;
 
@@ -230,4 +331,41 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_with_border() {
+        let img = ImageBuffer2D::generate(5, 4, |(x, y)| Luma::new([(x + 10 * y) as u8]));
+        let clamped = img.with_border(3, BorderMode::Clamp);
+        assert_eq!(clamped.get_pixel(-1, 0), img.get_pixel(0, 0));
+        assert_eq!(clamped.get_pixel(-3, 6), img.get_pixel(0, 3));
+        assert_eq!(clamped.get_pixel(2, 1), img.get_pixel(2, 1));
+
+        let zero = img.with_border(2, BorderMode::Zero);
+        assert_eq!(zero.get_pixel(6, 1), &Luma::new([0]));
+        assert_eq!(zero.get_pixel(4, 3), img.get_pixel(4, 3));
+
+        // Wrap and mirror agree with the corresponding padding functions.
+        for &(mode, ref padded) in &[
+            (BorderMode::Wrap, pad_wrap(&img, 2)),
+            (BorderMode::Mirror, pad_mirror(&img, 2)),
+        ] {
+            let bordered = img.with_border(2, mode);
+            for ((y, x), p) in padded.enumerate_pixels() {
+                assert_eq!(bordered.get_pixel(x as i64 - 2, y as i64 - 2), p);
+            }
+        }
+
+        let sub = img.sub_image(Rect::new(1, 1, 2, 2));
+        assert_eq!(
+            sub.with_border(1, BorderMode::Wrap).get_pixel(-1, 2),
+            img.get_pixel(2, 1)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_with_border_out_of_domain() {
+        let img = ImageBuffer2D::<Luma<u8>>::new(5, 4);
+        img.with_border(1, BorderMode::Clamp).get_pixel(-2, 0);
+    }
 }
@@ -1,5 +1,6 @@
 //! Contains the definitions of the various pixel types defined in this crate.
 
+use failure::Error;
 use num_traits::cast::cast;
 use num_traits::{Bounded, One, Zero};
 #[cfg(feature = "rand_integration")]
@@ -9,10 +10,12 @@ use rand::{
 };
 
 use core::{Pixel, PixelCast, Primitive};
+use helper::generic::round_saturate;
 
-use std::convert::From;
+use std::convert::{From, TryFrom};
 use std::ops::{
-    Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Rem, RemAssign, Sub, SubAssign,
+    Add, AddAssign, Deref, DerefMut, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Rem,
+    RemAssign, Sub, SubAssign,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -58,154 +61,154 @@ pub enum PixelType {
 // TODO: impl_op! macro
 
 macro_rules! impl_pixel_op {
-    ($pix_t:ident : $n_channels:expr, $op:ident, $op_fn:ident) => {
-        impl<P> $op for $pix_t<P>
+    ($op:ident, $op_fn:ident) => {
+        impl<P, const N: usize> $op for ChannelPixel<P, N>
         where
             P: Primitive,
         {
-            type Output = $pix_t<P>;
+            type Output = ChannelPixel<P, N>;
 
-            fn $op_fn(self, rhs: $pix_t<P>) -> $pix_t<P> {
-                let mut data = [<P as Zero>::zero(); $n_channels];
+            fn $op_fn(self, rhs: ChannelPixel<P, N>) -> ChannelPixel<P, N> {
+                let mut data = [<P as Zero>::zero(); N];
                 for ((n, s), r) in data.iter_mut().zip(self.data.iter()).zip(rhs.data.iter()) {
                     *n = s.$op_fn(r);
                 }
-                $pix_t { data }
+                ChannelPixel { data }
             }
         }
 
-        impl<'a, P> $op<$pix_t<P>> for &'a $pix_t<P>
+        impl<'a, P, const N: usize> $op<ChannelPixel<P, N>> for &'a ChannelPixel<P, N>
         where
             P: Primitive,
         {
-            type Output = $pix_t<P>;
+            type Output = ChannelPixel<P, N>;
 
-            fn $op_fn(self, rhs: $pix_t<P>) -> $pix_t<P> {
-                let mut data = [<P as Zero>::zero(); $n_channels];
+            fn $op_fn(self, rhs: ChannelPixel<P, N>) -> ChannelPixel<P, N> {
+                let mut data = [<P as Zero>::zero(); N];
                 for ((n, s), r) in data.iter_mut().zip(self.data.iter()).zip(rhs.data.iter()) {
                     *n = s.$op_fn(r);
                 }
-                $pix_t { data }
+                ChannelPixel { data }
             }
         }
 
-        impl<'a, P> $op<&'a $pix_t<P>> for $pix_t<P>
+        impl<'a, P, const N: usize> $op<&'a ChannelPixel<P, N>> for ChannelPixel<P, N>
         where
             P: Primitive,
         {
-            type Output = $pix_t<P>;
+            type Output = ChannelPixel<P, N>;
 
-            fn $op_fn(self, rhs: &'a $pix_t<P>) -> $pix_t<P> {
-                let mut data = [<P as Zero>::zero(); $n_channels];
+            fn $op_fn(self, rhs: &'a ChannelPixel<P, N>) -> ChannelPixel<P, N> {
+                let mut data = [<P as Zero>::zero(); N];
                 for ((n, s), r) in data.iter_mut().zip(self.data.iter()).zip(rhs.data.iter()) {
                     *n = s.$op_fn(r);
                 }
-                $pix_t { data }
+                ChannelPixel { data }
             }
         }
 
-        impl<'a, 'b, P> $op<&'a $pix_t<P>> for &'b $pix_t<P>
+        impl<'a, 'b, P, const N: usize> $op<&'a ChannelPixel<P, N>> for &'b ChannelPixel<P, N>
         where
             P: Primitive,
         {
-            type Output = $pix_t<P>;
+            type Output = ChannelPixel<P, N>;
 
-            fn $op_fn(self, rhs: &'a $pix_t<P>) -> $pix_t<P> {
-                let mut data = [<P as Zero>::zero(); $n_channels];
+            fn $op_fn(self, rhs: &'a ChannelPixel<P, N>) -> ChannelPixel<P, N> {
+                let mut data = [<P as Zero>::zero(); N];
                 for ((n, s), r) in data.iter_mut().zip(self.data.iter()).zip(rhs.data.iter()) {
                     *n = s.$op_fn(r);
                 }
-                $pix_t { data }
+                ChannelPixel { data }
             }
         }
 
-        impl<P> $op<P> for $pix_t<P>
+        impl<P, const N: usize> $op<P> for ChannelPixel<P, N>
         where
             P: Primitive,
         {
-            type Output = $pix_t<P>;
+            type Output = ChannelPixel<P, N>;
 
-            fn $op_fn(self, rhs: P) -> $pix_t<P> {
-                let mut data = [<P as Zero>::zero(); $n_channels];
+            fn $op_fn(self, rhs: P) -> ChannelPixel<P, N> {
+                let mut data = [<P as Zero>::zero(); N];
                 for (n, s) in data.iter_mut().zip(self.data.iter()) {
                     *n = s.$op_fn(rhs);
                 }
-                $pix_t { data }
+                ChannelPixel { data }
             }
         }
 
-        impl<'a, P> $op<P> for &'a $pix_t<P>
+        impl<'a, P, const N: usize> $op<P> for &'a ChannelPixel<P, N>
         where
             P: Primitive,
         {
-            type Output = $pix_t<P>;
+            type Output = ChannelPixel<P, N>;
 
-            fn $op_fn(self, rhs: P) -> $pix_t<P> {
-                let mut data = [<P as Zero>::zero(); $n_channels];
+            fn $op_fn(self, rhs: P) -> ChannelPixel<P, N> {
+                let mut data = [<P as Zero>::zero(); N];
                 for (n, s) in data.iter_mut().zip(self.data.iter()) {
                     *n = s.$op_fn(rhs);
                 }
-                $pix_t { data }
+                ChannelPixel { data }
             }
         }
 
-        impl<'a, P> $op<&'a P> for $pix_t<P>
+        impl<'a, P, const N: usize> $op<&'a P> for ChannelPixel<P, N>
         where
             P: Primitive,
         {
-            type Output = $pix_t<P>;
+            type Output = ChannelPixel<P, N>;
 
-            fn $op_fn(self, rhs: &'a P) -> $pix_t<P> {
-                let mut data = [<P as Zero>::zero(); $n_channels];
+            fn $op_fn(self, rhs: &'a P) -> ChannelPixel<P, N> {
+                let mut data = [<P as Zero>::zero(); N];
                 for (n, s) in data.iter_mut().zip(self.data.iter()) {
                     *n = s.$op_fn(rhs);
                 }
-                $pix_t { data }
+                ChannelPixel { data }
             }
         }
 
-        impl<'a, 'b, P> $op<&'a P> for &'b $pix_t<P>
+        impl<'a, 'b, P, const N: usize> $op<&'a P> for &'b ChannelPixel<P, N>
         where
             P: Primitive,
         {
-            type Output = $pix_t<P>;
+            type Output = ChannelPixel<P, N>;
 
-            fn $op_fn(self, rhs: &'a P) -> $pix_t<P> {
-                let mut data = [<P as Zero>::zero(); $n_channels];
+            fn $op_fn(self, rhs: &'a P) -> ChannelPixel<P, N> {
+                let mut data = [<P as Zero>::zero(); N];
                 for (n, s) in data.iter_mut().zip(self.data.iter()) {
                     *n = s.$op_fn(rhs);
                 }
-                $pix_t { data }
+                ChannelPixel { data }
             }
         }
     };
 }
 
 macro_rules! impl_pixel_op_assign {
-    ($pix_t:ident : $n_channels:expr, $op:ident, $op_fn:ident) => {
-        impl<P> $op for $pix_t<P>
+    ($op:ident, $op_fn:ident) => {
+        impl<P, const N: usize> $op for ChannelPixel<P, N>
         where
             P: Primitive,
         {
-            fn $op_fn(&mut self, rhs: $pix_t<P>) {
+            fn $op_fn(&mut self, rhs: ChannelPixel<P, N>) {
                 for (s, r) in self.data.iter_mut().zip(rhs.data.iter()) {
                     s.$op_fn(*r);
                 }
             }
         }
 
-        impl<'a, P> $op<&'a $pix_t<P>> for $pix_t<P>
+        impl<'a, P, const N: usize> $op<&'a ChannelPixel<P, N>> for ChannelPixel<P, N>
         where
             P: Primitive,
         {
-            fn $op_fn(&mut self, rhs: &'a $pix_t<P>) {
+            fn $op_fn(&mut self, rhs: &'a ChannelPixel<P, N>) {
                 for (s, r) in self.data.iter_mut().zip(rhs.data.iter()) {
                     s.$op_fn(*r);
                 }
             }
         }
 
-        impl<P> $op<P> for $pix_t<P>
+        impl<P, const N: usize> $op<P> for ChannelPixel<P, N>
         where
             P: Primitive,
         {
@@ -216,7 +219,7 @@ macro_rules! impl_pixel_op_assign {
             }
         }
 
-        impl<'a, P> $op<&'a P> for $pix_t<P>
+        impl<'a, P, const N: usize> $op<&'a P> for ChannelPixel<P, N>
         where
             P: Primitive,
         {
@@ -229,181 +232,553 @@ macro_rules! impl_pixel_op_assign {
     };
 }
 
-macro_rules! impl_pixels {
-    ( $( $(#[$attr:meta])* $name:ident, $n_channels:expr);+ ) =>
-    {$(
-        #[derive(Debug, Copy, Clone, PartialEq)]
-        $( #[$attr] )*
-        pub struct $name<P>
-            where P: Primitive
+/// Pixel type made of `N` channels of the same primitive type. The pixel types defined by this crate are aliases of
+/// this type, and pixel types with custom channel counts can be defined the same way.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ChannelPixel<P, const N: usize>
+where
+    P: Primitive,
+{
+    /// Pixel channels
+    pub data: [P; N],
+}
+
+/// Grayscale pixel type
+pub type Luma<P> = ChannelPixel<P, 1>;
+/// Grayscale with alpha pixel type
+pub type LumaA<P> = ChannelPixel<P, 2>;
+/// RGB pixel type
+pub type Rgb<P> = ChannelPixel<P, 3>;
+/// RGB with alpha pixel type
+pub type RgbA<P> = ChannelPixel<P, 4>;
+
+impl<P, const N: usize> ChannelPixel<P, N>
+where
+    P: Primitive,
+{
+    /// Construct a pixel from an array representing its' channels.
+    pub fn new(data: [P; N]) -> ChannelPixel<P, N> {
+        ChannelPixel { data }
+    }
+}
+
+impl_pixel_op!(Add, add);
+impl_pixel_op!(Sub, sub);
+impl_pixel_op!(Mul, mul);
+impl_pixel_op!(Div, div);
+impl_pixel_op!(Rem, rem);
+impl_pixel_op_assign!(AddAssign, add_assign);
+impl_pixel_op_assign!(SubAssign, sub_assign);
+impl_pixel_op_assign!(MulAssign, mul_assign);
+impl_pixel_op_assign!(DivAssign, div_assign);
+impl_pixel_op_assign!(RemAssign, rem_assign);
+
+impl<P, const N: usize> Zero for ChannelPixel<P, N>
+where
+    P: Primitive,
+{
+    fn zero() -> ChannelPixel<P, N> {
+        ChannelPixel {
+            data: [<P as Zero>::zero(); N],
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.data.iter().all(|p| p.is_zero())
+    }
+}
+
+impl<P, const N: usize> One for ChannelPixel<P, N>
+where
+    P: Primitive,
+{
+    fn one() -> ChannelPixel<P, N> {
+        ChannelPixel {
+            data: [<P as One>::one(); N],
+        }
+    }
+}
+
+impl<P, const N: usize> Bounded for ChannelPixel<P, N>
+where
+    P: Primitive,
+{
+    fn min_value() -> ChannelPixel<P, N> {
+        ChannelPixel {
+            data: [<P as Bounded>::min_value(); N],
+        }
+    }
+
+    fn max_value() -> ChannelPixel<P, N> {
+        ChannelPixel {
+            data: [<P as Bounded>::max_value(); N],
+        }
+    }
+}
+
+impl<P, const N: usize> From<[P; N]> for ChannelPixel<P, N>
+where
+    P: Primitive,
+{
+    fn from(array: [P; N]) -> ChannelPixel<P, N> {
+        ChannelPixel { data: array }
+    }
+}
+
+impl<'a, P, const N: usize> TryFrom<&'a [P]> for ChannelPixel<P, N>
+where
+    P: Primitive,
+{
+    type Error = Error;
+
+    /// Checked version of `Pixel::from_slice`, see `Pixel::try_from_slice`.
+    fn try_from(s: &'a [P]) -> Result<ChannelPixel<P, N>, Error> {
+        ChannelPixel::try_from_slice(s)
+    }
+}
+
+impl<P, const N: usize> Index<u8> for ChannelPixel<P, N>
+where
+    P: Primitive,
+{
+    type Output = P;
+
+    fn index(&self, index: u8) -> &P {
+        &self.data[index as usize]
+    }
+}
+
+impl<P, const N: usize> IndexMut<u8> for ChannelPixel<P, N>
+where
+    P: Primitive,
+{
+    fn index_mut(&mut self, index: u8) -> &mut P {
+        &mut self.data[index as usize]
+    }
+}
+
+impl<P, const N: usize> Pixel for ChannelPixel<P, N>
+where
+    P: Primitive,
+{
+    type Subpixel = P;
+
+    const N_CHANNELS: u32 = N as u32;
+
+    fn channels(&self) -> &[P] {
+        &self.data
+    }
+
+    fn channels_mut(&mut self) -> &mut [P] {
+        &mut self.data
+    }
+
+    fn from_slice(s: &[Self::Subpixel]) -> ChannelPixel<P, N> {
+        let mut p = ChannelPixel::zero();
+        for (n, e) in p.data.iter_mut().zip(s.iter()) {
+            *n = *e;
+        }
+        p
+    }
+
+    fn set_to_slice(&mut self, s: &[Self::Subpixel]) {
+        for (n, e) in self.data.iter_mut().zip(s.iter()) {
+            *n = *e;
+        }
+    }
+
+    fn map<F>(&self, f: F) -> Self
+    where
+        F: Fn(Self::Subpixel) -> Self::Subpixel,
+    {
+        let mut p = <Self as Zero>::zero();
+        for (dst, src) in p.channels_mut().iter_mut().zip(self.data.iter()) {
+            *dst = f(*src);
+        }
+        p
+    }
+
+    #[cfg(feature = "rand_integration")]
+    fn rand<R>(rng: &mut R) -> ChannelPixel<P, N>
+    where
+        R: Rng,
+        Standard: Distribution<P>,
+    {
+        let mut data = [P::zero(); N];
+        for c in data.iter_mut() {
+            *c = rng.gen();
+        }
+        Self { data }
+    }
+
+    #[cfg(feature = "rand_integration")]
+    fn rand_with_distr<D, R>(rng: &mut R, distr: &D) -> ChannelPixel<P, N>
+    where
+        R: Rng,
+        D: Distribution<P>,
+    {
+        let mut data = [P::zero(); N];
+        for c in data.iter_mut() {
+            *c = rng.sample(distr);
+        }
+        Self { data }
+    }
+}
+
+impl<S, O, const N: usize> PixelCast<ChannelPixel<O, N>, S, O> for ChannelPixel<S, N>
+where
+    O: Primitive,
+    S: Primitive,
+{
+    fn cast_from(&mut self, other: &ChannelPixel<O, N>) {
+        for (src, dst) in other.channels().iter().zip(self.channels_mut().iter_mut()) {
+            *dst = cast::<O, S>(*src).unwrap_or_else(<S as Zero>::zero);
+        }
+    }
+
+    fn cast_to(&self, other: &mut ChannelPixel<O, N>) {
+        for (dst, src) in other.channels_mut().iter_mut().zip(self.channels().iter()) {
+            *dst = cast::<S, O>(*src).unwrap_or_else(<O as Zero>::zero);
+        }
+    }
+}
+
+// Forward a binary operator of `ChannelPixel` to a color space pixel type, both between pixels and with a scalar.
+macro_rules! impl_color_space_op {
+    ($name:ident, $op:ident, $op_fn:ident) => {
+        impl<P> $op for $name<P>
+        where
+            P: Primitive,
+        {
+            type Output = $name<P>;
+
+            fn $op_fn(self, rhs: $name<P>) -> $name<P> {
+                $name(self.0.$op_fn(rhs.0))
+            }
+        }
+
+        impl<'a, P> $op<&'a $name<P>> for $name<P>
+        where
+            P: Primitive,
+        {
+            type Output = $name<P>;
+
+            fn $op_fn(self, rhs: &'a $name<P>) -> $name<P> {
+                $name(self.0.$op_fn(rhs.0))
+            }
+        }
+
+        impl<'a, P> $op<$name<P>> for &'a $name<P>
+        where
+            P: Primitive,
+        {
+            type Output = $name<P>;
+
+            fn $op_fn(self, rhs: $name<P>) -> $name<P> {
+                $name(self.0.$op_fn(rhs.0))
+            }
+        }
+
+        impl<'a, 'b, P> $op<&'a $name<P>> for &'b $name<P>
+        where
+            P: Primitive,
+        {
+            type Output = $name<P>;
+
+            fn $op_fn(self, rhs: &'a $name<P>) -> $name<P> {
+                $name(self.0.$op_fn(rhs.0))
+            }
+        }
+
+        impl<P> $op<P> for $name<P>
+        where
+            P: Primitive,
+        {
+            type Output = $name<P>;
+
+            fn $op_fn(self, rhs: P) -> $name<P> {
+                $name(self.0.$op_fn(rhs))
+            }
+        }
+
+        impl<'a, P> $op<P> for &'a $name<P>
+        where
+            P: Primitive,
+        {
+            type Output = $name<P>;
+
+            fn $op_fn(self, rhs: P) -> $name<P> {
+                $name(self.0.$op_fn(rhs))
+            }
+        }
+
+        impl<'a, P> $op<&'a P> for $name<P>
+        where
+            P: Primitive,
         {
-            /// Pixel channels
-            pub data: [P; $n_channels]
+            type Output = $name<P>;
+
+            fn $op_fn(self, rhs: &'a P) -> $name<P> {
+                $name(self.0.$op_fn(rhs))
+            }
+        }
+
+        impl<'a, 'b, P> $op<&'a P> for &'b $name<P>
+        where
+            P: Primitive,
+        {
+            type Output = $name<P>;
+
+            fn $op_fn(self, rhs: &'a P) -> $name<P> {
+                $name(self.0.$op_fn(rhs))
+            }
+        }
+    };
+}
+
+// Forward an assignment operator of `ChannelPixel` to a color space pixel type.
+macro_rules! impl_color_space_op_assign {
+    ($name:ident, $op:ident, $op_fn:ident) => {
+        impl<P> $op for $name<P>
+        where
+            P: Primitive,
+        {
+            fn $op_fn(&mut self, rhs: $name<P>) {
+                self.0.$op_fn(rhs.0);
+            }
+        }
+
+        impl<'a, P> $op<&'a $name<P>> for $name<P>
+        where
+            P: Primitive,
+        {
+            fn $op_fn(&mut self, rhs: &'a $name<P>) {
+                self.0.$op_fn(rhs.0);
+            }
+        }
+
+        impl<P> $op<P> for $name<P>
+        where
+            P: Primitive,
+        {
+            fn $op_fn(&mut self, rhs: P) {
+                self.0.$op_fn(rhs);
+            }
+        }
+
+        impl<'a, P> $op<&'a P> for $name<P>
+        where
+            P: Primitive,
+        {
+            fn $op_fn(&mut self, rhs: &'a P) {
+                self.0.$op_fn(rhs);
+            }
         }
+    };
+}
+
+// Define a three channel pixel type for a color space as a wrapper around `Rgb`'s storage, distinct from `Rgb` so that
+// images in different color spaces cannot be mixed up. Every trait is forwarded to the wrapped `ChannelPixel`.
+macro_rules! color_space_pixel {
+    ($name:ident, $doc:expr) => {
+        #[doc = $doc]
+        #[repr(transparent)]
+        #[derive(Debug, Copy, Clone, PartialEq)]
+        pub struct $name<P>(pub ChannelPixel<P, 3>)
+        where
+            P: Primitive;
 
         impl<P> $name<P>
-            where P: Primitive
+        where
+            P: Primitive,
         {
             /// Construct a pixel from an array representing its' channels.
-            pub fn new(data: [P; $n_channels]) -> $name<P> {
-                $name { data }
+            pub fn new(data: [P; 3]) -> $name<P> {
+                $name(ChannelPixel::new(data))
+            }
+        }
+
+        impl<P> Deref for $name<P>
+        where
+            P: Primitive,
+        {
+            type Target = ChannelPixel<P, 3>;
+
+            fn deref(&self) -> &ChannelPixel<P, 3> {
+                &self.0
+            }
+        }
+
+        impl<P> DerefMut for $name<P>
+        where
+            P: Primitive,
+        {
+            fn deref_mut(&mut self) -> &mut ChannelPixel<P, 3> {
+                &mut self.0
             }
         }
 
-        impl_pixel_op!($name: $n_channels, Add, add);
-        impl_pixel_op!($name: $n_channels, Sub, sub);
-        impl_pixel_op!($name: $n_channels, Mul, mul);
-        impl_pixel_op!($name: $n_channels, Div, div);
-        impl_pixel_op!($name: $n_channels, Rem, rem);
-        impl_pixel_op_assign!($name: $n_channels, AddAssign, add_assign);
-        impl_pixel_op_assign!($name: $n_channels, SubAssign, sub_assign);
-        impl_pixel_op_assign!($name: $n_channels, MulAssign, mul_assign);
-        impl_pixel_op_assign!($name: $n_channels, DivAssign, div_assign);
-        impl_pixel_op_assign!($name: $n_channels, RemAssign, rem_assign);
+        impl_color_space_op!($name, Add, add);
+        impl_color_space_op!($name, Sub, sub);
+        impl_color_space_op!($name, Mul, mul);
+        impl_color_space_op!($name, Div, div);
+        impl_color_space_op!($name, Rem, rem);
+        impl_color_space_op_assign!($name, AddAssign, add_assign);
+        impl_color_space_op_assign!($name, SubAssign, sub_assign);
+        impl_color_space_op_assign!($name, MulAssign, mul_assign);
+        impl_color_space_op_assign!($name, DivAssign, div_assign);
+        impl_color_space_op_assign!($name, RemAssign, rem_assign);
 
         impl<P> Zero for $name<P>
-            where P: Primitive
+        where
+            P: Primitive,
         {
             fn zero() -> $name<P> {
-                $name { data: [<P as Zero>::zero(); $n_channels] }
+                $name(ChannelPixel::zero())
             }
 
             fn is_zero(&self) -> bool {
-                self.data.iter().map(|p| p.is_zero()).fold(true, |acc, b| b && acc)
+                self.0.is_zero()
             }
         }
 
         impl<P> One for $name<P>
-            where P: Primitive
+        where
+            P: Primitive,
         {
             fn one() -> $name<P> {
-                $name { data: [<P as One>::one(); $n_channels ] }
+                $name(ChannelPixel::one())
             }
         }
 
         impl<P> Bounded for $name<P>
-            where P: Primitive
+        where
+            P: Primitive,
         {
             fn min_value() -> $name<P> {
-                $name { data: [<P as Bounded>::min_value(); $n_channels ] }
+                $name(ChannelPixel::min_value())
             }
 
             fn max_value() -> $name<P> {
-                $name { data: [<P as Bounded>::max_value(); $n_channels ] }
+                $name(ChannelPixel::max_value())
             }
         }
 
-        impl<P> From<[P; $n_channels]> for $name<P>
-            where P: Primitive
+        impl<P> From<[P; 3]> for $name<P>
+        where
+            P: Primitive,
         {
-            fn from(array: [P; $n_channels]) -> $name<P> {
-                $name { data: array }
+            fn from(array: [P; 3]) -> $name<P> {
+                $name::new(array)
+            }
+        }
+
+        impl<'a, P> TryFrom<&'a [P]> for $name<P>
+        where
+            P: Primitive,
+        {
+            type Error = Error;
+
+            /// Checked version of `Pixel::from_slice`, see `Pixel::try_from_slice`.
+            fn try_from(s: &'a [P]) -> Result<$name<P>, Error> {
+                $name::try_from_slice(s)
             }
         }
 
         impl<P> Index<u8> for $name<P>
-            where P: Primitive
+        where
+            P: Primitive,
         {
             type Output = P;
 
             fn index(&self, index: u8) -> &P {
-                &self.data[index as usize]
+                &self.0[index]
             }
         }
 
         impl<P> IndexMut<u8> for $name<P>
-            where P: Primitive
+        where
+            P: Primitive,
         {
             fn index_mut(&mut self, index: u8) -> &mut P {
-                &mut self.data[index as usize]
+                &mut self.0[index]
             }
         }
 
         impl<P> Pixel for $name<P>
-            where P: Primitive
+        where
+            P: Primitive,
         {
             type Subpixel = P;
 
-            const N_CHANNELS: u32 = $n_channels;
+            const N_CHANNELS: u32 = 3;
 
-            fn channels(&self) -> &[P] { &self.data }
+            fn channels(&self) -> &[P] {
+                self.0.channels()
+            }
 
-            fn channels_mut(&mut self) -> &mut [P] { &mut self.data }
+            fn channels_mut(&mut self) -> &mut [P] {
+                self.0.channels_mut()
+            }
 
-            fn from_slice(s: &[Self::Subpixel]) -> $name<P> {
-                let mut p = $name::zero();
-                for (n, e) in p.data.iter_mut().zip(s.iter()) {
-                    *n = *e;
-                }
-                p
+            fn from_slice(s: &[P]) -> $name<P> {
+                $name(ChannelPixel::from_slice(s))
             }
 
-            fn set_to_slice(&mut self, s: &[Self::Subpixel]) {
-                for (n, e) in self.data.iter_mut().zip(s.iter()) {
-                    *n = *e;
-                }
+            fn set_to_slice(&mut self, s: &[P]) {
+                self.0.set_to_slice(s);
             }
 
             fn map<F>(&self, f: F) -> Self
-                where F: Fn(Self::Subpixel) -> Self::Subpixel
+            where
+                F: Fn(P) -> P,
             {
-                let mut p = <Self as Zero>::zero();
-                for (dst, src) in p.channels_mut().into_iter().zip(self.data.into_iter()) {
-                    *dst = f(*src);
-                }
-                p
+                $name(self.0.map(f))
             }
 
             #[cfg(feature = "rand_integration")]
             fn rand<R>(rng: &mut R) -> $name<P>
-                where R: Rng,
-                      Standard: Distribution<[P; $n_channels]>,
-
+            where
+                R: Rng,
+                Standard: Distribution<P>,
             {
-                Self { data: rng.gen() }
+                $name(ChannelPixel::rand(rng))
             }
 
             #[cfg(feature = "rand_integration")]
             fn rand_with_distr<D, R>(rng: &mut R, distr: &D) -> $name<P>
-                where R: Rng,
-                      D: Distribution<P>
+            where
+                R: Rng,
+                D: Distribution<P>,
             {
-                let mut data = [P::zero(); $n_channels];
-                for c in data.iter_mut().take($n_channels) {
-                    *c = rng.sample(distr);
-                }
-                Self { data }
+                $name(ChannelPixel::rand_with_distr(rng, distr))
             }
         }
 
         impl<S, O> PixelCast<$name<O>, S, O> for $name<S>
-            where O: Primitive,
-                  S: Primitive
+        where
+            O: Primitive,
+            S: Primitive,
         {
             fn cast_from(&mut self, other: &$name<O>) {
-                for (src, dst) in other.channels().into_iter().zip(self.channels_mut().into_iter()) {
-                    *dst = cast::<O, S>(src.clone()).unwrap_or(<S as Zero>::zero());
-                }
+                self.0.cast_from(&other.0);
             }
 
             fn cast_to(&self, other: &mut $name<O>) {
-                for (dst, src) in other.channels_mut().into_iter().zip(self.channels().into_iter()) {
-                    *dst = cast::<S, O>(src.clone()).unwrap_or(<O as Zero>::zero());
-                }
+                self.0.cast_to(&mut other.0);
             }
         }
-    )+}
+    };
 }
 
-impl_pixels!(
-    /// Grayscale pixel type
-    Luma, 1;
-    /// Grayscale with alpha pixel type
-    LumaA, 2;
-    /// RGB pixel type
-    Rgb, 3;
-    /// RGB with alpha pixel type
-    RgbA, 4
+color_space_pixel!(
+    Xyz,
+    "CIE 1931 XYZ pixel type, with channels `[X, Y, Z]`. The conversions of this crate use the D65 white point, and \
+     scale Y to 1 for white."
+);
+color_space_pixel!(
+    Lab,
+    "CIE L\\*a\\*b\\* pixel type, with channels `[L, a, b]`. L ranges from 0 for black to 100 for white."
 );
 
 impl<P> From<LumaA<P>> for Luma<P>
@@ -459,9 +834,89 @@ where
     }
 }
 
+impl<P> From<Luma<P>> for Rgb<P>
+where
+    P: Primitive,
+{
+    fn from(pixel: Luma<P>) -> Rgb<P> {
+        Rgb {
+            data: [pixel.data[0]; 3],
+        }
+    }
+}
+
+impl<'a, P> From<&'a Luma<P>> for Rgb<P>
+where
+    P: Primitive,
+{
+    fn from(pixel: &'a Luma<P>) -> Rgb<P> {
+        Rgb {
+            data: [pixel.data[0]; 3],
+        }
+    }
+}
+
+impl<P> From<Rgb<P>> for Luma<P>
+where
+    P: Primitive,
+{
+    /// Compute the luminance of the pixel using the ITU-R BT.601 weights.
+    fn from(pixel: Rgb<P>) -> Luma<P> {
+        let weights = [0.299, 0.587, 0.114];
+        let l = pixel
+            .data
+            .iter()
+            .zip(weights.iter())
+            .fold(0f32, |acc, (c, w)| acc + w * cast::<P, f32>(*c).unwrap());
+        Luma {
+            data: [round_saturate(l)],
+        }
+    }
+}
+
+impl<'a, P> From<&'a Rgb<P>> for Luma<P>
+where
+    P: Primitive,
+{
+    /// Compute the luminance of the pixel using the ITU-R BT.601 weights.
+    fn from(pixel: &'a Rgb<P>) -> Luma<P> {
+        Luma::from(*pixel)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use core::Luma;
+    use core::{ChannelPixel, Lab, Luma, LumaA, Pixel, PixelCast, Rgb, RgbA, Xyz};
+
+    use num_traits::{Bounded, One, Zero};
+
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_try_from_slice() {
+        let data = [1u8, 2, 3, 4, 5];
+        assert_eq!(Rgb::try_from(&data[..3]).unwrap(), Rgb::new([1, 2, 3]));
+        assert_eq!(RgbA::try_from(&data[1..]).unwrap(), RgbA::new([2, 3, 4, 5]));
+        // Extra channels are ignored, like with `from_slice`.
+        assert_eq!(Luma::try_from(&data[..]).unwrap(), Luma::new([1]));
+        assert!(RgbA::try_from(&data[..3]).is_err());
+        assert!(Luma::<u8>::try_from(&data[..0]).is_err());
+
+        let lab = [50f32, 10.];
+        assert!(Lab::try_from(&lab[..]).is_err());
+        assert_eq!(
+            Lab::try_from_slice(&[50f32, 10., -5.]).unwrap(),
+            Lab::new([50., 10., -5.])
+        );
+        assert!(ChannelPixel::<u16, 2>::try_from_slice(&[7]).is_err());
+    }
+
+    #[test]
+    fn test_zero_pixel() {
+        assert_eq!(Luma::<u8>::zero_pixel(), Luma::new([0]));
+        assert_eq!(RgbA::<f32>::zero_pixel(), RgbA::new([0.; 4]));
+        assert_eq!(ChannelPixel::<i16, 5>::zero_pixel().data, [0; 5]);
+    }
 
     #[test]
     fn test_pixel_add() {
@@ -484,4 +939,69 @@ mod tests {
         let l4 = Luma::new([3u8]);
         assert_eq!(l3 - 5u8, l4);
     }
+
+    #[test]
+    fn test_pixel_types() {
+        assert_eq!(Luma::<u8>::N_CHANNELS, 1);
+        assert_eq!(LumaA::<u8>::N_CHANNELS, 2);
+        assert_eq!(Rgb::<u16>::N_CHANNELS, 3);
+        assert_eq!(RgbA::<f32>::N_CHANNELS, 4);
+
+        let mut p = Rgb::new([1u8, 2, 3]);
+        assert_eq!(p.channels(), &[1, 2, 3]);
+        p.channels_mut()[1] = 5;
+        p[2] = 7;
+        assert_eq!(p, Rgb::from([1, 5, 7]));
+        assert_eq!(Rgb::from_slice(&[4u8, 5, 6, 7]), Rgb::new([4, 5, 6]));
+        assert_eq!(p.map(|c| c * 2), Rgb::new([2, 10, 14]));
+        assert_eq!(p.sum(), 13);
+        assert!(RgbA::<u8>::zero().is_zero());
+        assert_eq!(LumaA::<u8>::max_value(), LumaA::new([255, 255]));
+
+        let mut q = Rgb::<u16>::zero();
+        q.cast_from(&p);
+        assert_eq!(q, Rgb::new([1u16, 5, 7]));
+    }
+
+    #[test]
+    fn test_custom_channel_count() {
+        let p = ChannelPixel::new([1u8, 2, 3, 4, 5]);
+        assert_eq!(ChannelPixel::<u8, 5>::N_CHANNELS, 5);
+        assert_eq!(p + 1u8, ChannelPixel::new([2, 3, 4, 5, 6]));
+    }
+
+    #[test]
+    fn test_color_space_pixel() {
+        let mut lab = Lab::new([50f32, 10., -20.]);
+        assert_eq!(lab + Lab::new([1., 2., 3.]), Lab::new([51., 12., -17.]));
+        assert_eq!(&lab - 10., Lab::new([40., 0., -30.]));
+        assert_eq!(lab * 2., Lab::new([100., 20., -40.]));
+        lab /= 2.;
+        assert_eq!(lab, Lab::from([25., 5., -10.]));
+        assert_eq!(lab[2], -10.);
+        assert_eq!(lab.data, [25., 5., -10.]);
+        assert_eq!(Lab::<f32>::one(), Lab::new([1.; 3]));
+        assert_eq!(Lab::<f32>::N_CHANNELS, 3);
+
+        let mut cast = Xyz::<u8>::zero();
+        cast.cast_from(&Xyz::new([0.5f32, 12.7, -3.]));
+        assert_eq!(cast, Xyz::new([0, 12, 0]));
+    }
+
+    #[test]
+    fn test_luma_to_rgb() {
+        let rgb: Rgb<u8> = Luma::new([87u8]).into();
+        assert_eq!(rgb, Rgb::new([87, 87, 87]));
+        assert_eq!(Rgb::from(&Luma::new([0.25f32])), Rgb::new([0.25; 3]));
+    }
+
+    #[test]
+    fn test_rgb_to_luma() {
+        let luma: Luma<u8> = Rgb::new([100u8, 200, 50]).into();
+        // 0.299 * 100 + 0.587 * 200 + 0.114 * 50 = 153
+        assert_eq!(luma, Luma::new([153]));
+        assert_eq!(Luma::from(&Rgb::new([255u8; 3])), Luma::new([255]));
+        let luma = Luma::from(Rgb::new([1f32, 0., 0.]));
+        assert!((luma.data[0] - 0.299).abs() < 1e-6);
+    }
 }
@@ -84,7 +84,7 @@ impl Rect {
     }
 
     /// Test whether the Rect fits in the given image.
-    pub fn fits_image<P>(&self, img: &Image2D<P>) -> bool
+    pub fn fits_image<P>(&self, img: &dyn Image2D<P>) -> bool
     where
         P: Pixel,
     {
@@ -92,7 +92,7 @@ impl Rect {
     }
 
     /// Crop the `Rect` to the biggest sub-`Rect` that can fit `img` if it exists, `None` otherwise.
-    pub fn crop_to_image<P>(&self, img: &Image2D<P>) -> Option<Rect>
+    pub fn crop_to_image<P>(&self, img: &dyn Image2D<P>) -> Option<Rect>
     where
         P: Pixel,
     {
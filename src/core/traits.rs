@@ -1,5 +1,6 @@
 //! Contains the definitions of the various traits used in this crate.
 
+use failure::Error;
 use num_traits::{Bounded, NumAssign, NumCast, NumRef, Zero};
 #[cfg(feature = "rand_integration")]
 use rand::{
@@ -40,6 +41,26 @@ where
 {
 }
 
+/// Implemented for primitive types with a fixed, platform independent byte representation.
+pub trait ByteRepr: Primitive {
+    /// Append the little-endian bytes of the value to `out`.
+    fn extend_le_bytes(&self, out: &mut Vec<u8>);
+}
+
+macro_rules! impl_byte_repr {
+    ($($t:ty),+) => {
+        $(
+        impl ByteRepr for $t {
+            fn extend_le_bytes(&self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.to_le_bytes());
+            }
+        }
+        )+
+    };
+}
+
+impl_byte_repr!(u8, i8, u16, i16, u32, i32, u64, i64, f32, f64);
+
 /// This trait must be implemented for the types you want to store in an image.
 pub trait Pixel: Clone + PartialEq + Sync + Send + Zero {
     /// Type of an individual pixel component.
@@ -60,6 +81,28 @@ pub trait Pixel: Clone + PartialEq + Sync + Send + Zero {
     /// number of channels in the pixel.
     fn from_slice(s: &[Self::Subpixel]) -> Self;
 
+    /// Create a new pixel from a slice, checking its length. Channels past the number of channels of the pixel are
+    /// ignored.
+    ///
+    /// **Error**: if s.len() is less than the number of channels in the pixel.
+    fn try_from_slice(s: &[Self::Subpixel]) -> Result<Self, Error> {
+        ensure!(
+            s.len() >= Self::N_CHANNELS as usize,
+            "Slice of length {} is too short for a pixel of {} channels",
+            s.len(),
+            Self::N_CHANNELS
+        );
+        Ok(Self::from_slice(s))
+    }
+
+    /// Return the pixel whose channels are all zero.
+    fn zero_pixel() -> Self
+    where
+        Self::Subpixel: Zero,
+    {
+        <Self as Zero>::zero()
+    }
+
     /// Set the value of the pixel from a slice.
     ///
     /// **Panics**: the length of the slice is not checked, so this function will panic if s.len() is less than the
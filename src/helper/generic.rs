@@ -1,3 +1,5 @@
+use core::Primitive;
+
 use num_traits::{Float, NumCast};
 
 pub fn f64_to_float<T>(f: f64) -> T
@@ -6,3 +8,46 @@ where
 {
     <T as NumCast>::from::<f64>(f).unwrap()
 }
+
+/// Convert a floating point value to the given primitive type. The value is rounded to the nearest integer if `T` is
+/// an integer type, and saturated to the bounds of `T` if it falls out of its range.
+pub fn round_saturate<T>(f: f32) -> T
+where
+    T: Primitive,
+{
+    round_saturate_f64(<f64 as From<f32>>::from(f))
+}
+
+/// Double precision version of `round_saturate`.
+pub fn round_saturate_f64<T>(f: f64) -> T
+where
+    T: Primitive,
+{
+    let is_integer = <T as NumCast>::from(0.5f32)
+        .unwrap_or_else(T::zero)
+        .is_zero();
+    let f = if is_integer { f.round() } else { f };
+    match <T as NumCast>::from(f) {
+        Some(v) => v,
+        None if f > 0. => T::max_value(),
+        None => T::min_value(),
+    }
+}
+
+/// Apply the inverse sRGB transfer function, converting a normalized sRGB value to linear light.
+pub fn srgb_decode(c: f32) -> f32 {
+    if c <= 0.040_45 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Apply the sRGB transfer function, converting a normalized linear light value to sRGB.
+pub fn srgb_encode(c: f32) -> f32 {
+    if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1. / 2.4) - 0.055
+    }
+}
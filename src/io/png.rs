@@ -1,8 +1,8 @@
 //! PNG codec.
 
 use core::{
-    BitDepth, DynamicImage, Image2D, ImageBuffer2D, ImageType, Luma, LumaA, Pixel, PixelType, Rgb,
-    RgbA,
+    BitDepth, DynamicImage, Image2D, ImageBuffer2D, ImageType, Luma, LumaA, Pixel, PixelType,
+    Primitive, Rgb, RgbA,
 };
 
 use byteorder::{BigEndian, ByteOrder, ReadBytesExt};
@@ -12,7 +12,10 @@ use io::traits::{ImageDecoder, ImageEncoder};
 use png;
 use png::HasParameters;
 
-use std::io::{Cursor, Read, Write};
+use std::fs::File;
+use std::io::{BufReader, Cursor, Read, Write};
+use std::marker::PhantomData;
+use std::path::Path;
 
 /// PNG decoder type
 pub struct Decoder<R>
@@ -291,6 +294,139 @@ where
     pub fn depth(&self) -> BitDepth {
         self.depth
     }
+
+    /// Return the image dimensions `(width, height)`.
+    pub fn dimensions(&self) -> (u32, u32) {
+        let info = self.reader.info();
+        (info.width, info.height)
+    }
+}
+
+/// Read the dimensions `(width, height)` and the type of a PNG image from the header of the file at `path`, without
+/// decoding the pixel data.
+pub fn png_dimensions<P>(path: P) -> Result<(u32, u32, ImageType), Error>
+where
+    P: AsRef<Path>,
+{
+    let decoder = Decoder::new(BufReader::new(File::open(path)?))?;
+    let (w, h) = decoder.dimensions();
+    Ok((w, h, (decoder.image_channels(), decoder.depth())))
+}
+
+/// Implemented for the subpixel types that can be decoded from a PNG image.
+pub trait PngSubpixel: Primitive {
+    /// Bit depth of the PNG images storing this subpixel type.
+    const DEPTH: BitDepth;
+
+    /// Convert a row of raw PNG bytes into subpixel values.
+    fn from_png_bytes(bytes: &[u8]) -> Result<Vec<Self>, Error>;
+}
+
+impl PngSubpixel for u8 {
+    const DEPTH: BitDepth = BitDepth::_8;
+
+    fn from_png_bytes(bytes: &[u8]) -> Result<Vec<u8>, Error> {
+        Ok(bytes.to_vec())
+    }
+}
+
+impl PngSubpixel for u16 {
+    const DEPTH: BitDepth = BitDepth::_16;
+
+    fn from_png_bytes(bytes: &[u8]) -> Result<Vec<u16>, Error> {
+        bytes_to_vec_u16::<BigEndian>(bytes)
+    }
+}
+
+/// Iterator over the rows of a PNG image, decoding a single row at a time. Created by `png_rows`.
+pub struct PngRows<P>
+where
+    P: Pixel,
+{
+    reader: png::Reader<BufReader<File>>,
+    remaining: u32,
+    _pixel: PhantomData<P>,
+}
+
+impl<P> Iterator for PngRows<P>
+where
+    P: Pixel,
+    P::Subpixel: PngSubpixel,
+{
+    type Item = Result<Vec<P>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let row = match self.reader.next_row() {
+            Ok(Some(row)) => row,
+            Ok(None) => {
+                self.remaining = 0;
+                return Some(Err(DecodingError::Internal.into()));
+            }
+            Err(e) => {
+                self.remaining = 0;
+                return Some(Err(DecodingError::Decoder(e).into()));
+            }
+        };
+        self.remaining -= 1;
+        Some(P::Subpixel::from_png_bytes(row).map(|subpixels| {
+            subpixels
+                .chunks(P::N_CHANNELS as usize)
+                .map(P::from_slice)
+                .collect()
+        }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining as usize, Some(self.remaining as usize))
+    }
+}
+
+impl<P> ExactSizeIterator for PngRows<P>
+where
+    P: Pixel,
+    P::Subpixel: PngSubpixel,
+{
+}
+
+/// Open the PNG image at `path` for decoding one row at a time, from top to bottom. Only a single row of the image is
+/// kept in memory, allowing to process images too large to be decoded at once.
+///
+/// **Error**: if the file can't be opened or its header decoded, if the type of the image doesn't match the pixel type
+/// `P`, or if the image is interlaced. Decoding errors occurring later are yielded by the iterator, which stops after
+/// the first one.
+pub fn png_rows<P, Q>(path: Q) -> Result<PngRows<P>, Error>
+where
+    P: Pixel,
+    P::Subpixel: PngSubpixel,
+    Q: AsRef<Path>,
+{
+    let decoder = Decoder::new(BufReader::new(File::open(path)?))?;
+    let channels = match P::N_CHANNELS {
+        1 => PixelType::Luma,
+        2 => PixelType::LumaA,
+        3 => PixelType::Rgb,
+        4 => PixelType::RgbA,
+        n => bail!(
+            "Pixels with {} channels can't be decoded from a PNG image",
+            n
+        ),
+    };
+    if (channels, P::Subpixel::DEPTH) != (decoder.channels, decoder.depth) {
+        return Err(DecodingError::IncorrectPixelType(decoder.channels, decoder.depth).into());
+    }
+    ensure!(
+        !decoder.reader.info().interlaced,
+        "Interlaced PNG images can't be decoded row by row"
+    );
+    let remaining = decoder.reader.info().height;
+    Ok(PngRows {
+        reader: decoder.reader,
+        remaining,
+        _pixel: PhantomData,
+    })
 }
 
 impl<R> ImageDecoder for Decoder<R>
@@ -467,6 +603,7 @@ mod tests {
     use io::png::*;
 
     use num_traits::{NumCast, Zero};
+    use tempfile::tempdir;
 
     use std::env::current_dir;
     use std::fmt::Debug;
@@ -648,4 +785,72 @@ mod tests {
         let img = mk_test_img::<Rgb<u16>, u16>();
         helper_test_write_roundtrip_u16(img, |d| d.read_rgb_u16());
     }
+
+    #[test]
+    fn test_png_dimensions() {
+        assert_eq!(
+            png_dimensions("test_data/io/png/rgb_16bit.png").unwrap(),
+            (32, 32, (PixelType::Rgb, BitDepth::_16))
+        );
+        assert_eq!(
+            png_dimensions("test_data/io/png/grayscale_alpha_8bit.png").unwrap(),
+            (32, 32, (PixelType::LumaA, BitDepth::_8))
+        );
+        assert!(png_dimensions("test_data/io/png/missing.png").is_err());
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("test_png_dimensions.png");
+        let img = ImageBuffer2D::<Luma<u8>>::new(17, 5);
+        Encoder8::new()
+            .write(File::create(&path).unwrap(), &img)
+            .unwrap();
+        assert_eq!(
+            png_dimensions(&path).unwrap(),
+            (17, 5, (PixelType::Luma, BitDepth::_8))
+        );
+    }
+
+    fn helper_test_png_rows<F, P>(img_path: &'static str, read_fn: F)
+    where
+        F: FnOnce(Decoder<File>) -> Result<ImageBuffer2D<P>, Error>,
+        P: Pixel + Debug,
+        P::Subpixel: PngSubpixel,
+    {
+        let expected = read_fn(Decoder::new(File::open(img_path).unwrap()).unwrap()).unwrap();
+        let rows = png_rows::<P, _>(img_path).unwrap();
+        assert_eq!(rows.len(), expected.height() as usize);
+        let pixels = rows
+            .flat_map(|row| {
+                let row = row.unwrap();
+                assert_eq!(row.len(), expected.width() as usize);
+                row
+            })
+            .collect();
+        let img = ImageBuffer2D::from_vec(expected.width(), expected.height(), pixels).unwrap();
+        assert_eq!(img, expected);
+    }
+
+    #[test]
+    fn test_png_rows() {
+        helper_test_png_rows("test_data/io/png/grayscale_8bit.png", |d| d.read_luma_u8());
+        helper_test_png_rows("test_data/io/png/grayscale_16bit.png", |d| {
+            d.read_luma_u16()
+        });
+        helper_test_png_rows("test_data/io/png/grayscale_alpha_8bit.png", |d| {
+            d.read_luma_alpha_u8()
+        });
+        helper_test_png_rows("test_data/io/png/grayscale_alpha_16bit.png", |d| {
+            d.read_luma_alpha_u16()
+        });
+        helper_test_png_rows("test_data/io/png/rgb_8bit.png", |d| d.read_rgb_u8());
+        helper_test_png_rows("test_data/io/png/rgb_16bit.png", |d| d.read_rgb_u16());
+        helper_test_png_rows("test_data/io/png/rgba_8bit.png", |d| d.read_rgb_alpha_u8());
+        helper_test_png_rows("test_data/io/png/rgba_16bit.png", |d| {
+            d.read_rgb_alpha_u16()
+        });
+
+        assert!(png_rows::<Rgb<u16>, _>("test_data/io/png/rgb_8bit.png").is_err());
+        assert!(png_rows::<Luma<u8>, _>("test_data/io/png/rgb_8bit.png").is_err());
+        assert!(png_rows::<Rgb<u8>, _>("test_data/io/png/missing.png").is_err());
+    }
 }
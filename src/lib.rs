@@ -10,6 +10,10 @@ extern crate num_traits;
 extern crate png;
 #[cfg(feature = "rand_integration")]
 extern crate rand;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "fft")]
+extern crate rustfft;
 #[cfg(test)]
 extern crate tempfile;
 extern crate tiff;
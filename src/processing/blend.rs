@@ -0,0 +1,418 @@
+//! Contains functions combining several images into one.
+
+use core::{Image2D, ImageBuffer2D, Luma, Pixel, Rgb};
+use helper::generic::{round_saturate, round_saturate_f64};
+
+use failure::Error;
+use num_traits::NumCast;
+
+/// Compute the per-pixel weighted average of a stack of images, accumulating in floating point. This is the core of
+/// exposure fusion. The output pixel type `Q` must have the same number of channels as `P`.
+///
+/// **Error**: if the number of images and weights differ, if the stack is empty, if the image dimensions or channel
+/// counts differ, or if the weights sum to zero.
+pub fn weighted_merge<P, Q>(
+    imgs: &[&dyn Image2D<P>],
+    weights: &[f32],
+) -> Result<ImageBuffer2D<Q>, Error>
+where
+    P: Pixel,
+    Q: Pixel<Subpixel = f32>,
+{
+    ensure!(
+        imgs.len() == weights.len(),
+        "Number of images and weights differ: {} != {}",
+        imgs.len(),
+        weights.len()
+    );
+    ensure!(!imgs.is_empty(), "Cannot merge an empty stack of images");
+    ensure!(
+        P::N_CHANNELS == Q::N_CHANNELS,
+        "Input and output pixel types have different channel counts: {} != {}",
+        P::N_CHANNELS,
+        Q::N_CHANNELS
+    );
+    let (w, h) = imgs[0].dimensions();
+    if imgs.iter().any(|img| img.dimensions() != (w, h)) {
+        bail!("Image dimensions do not match");
+    }
+    let total = weights.iter().sum::<f32>();
+    ensure!(total != 0., "Weights sum to zero");
+
+    let n_channels = P::N_CHANNELS as usize;
+    let mut accu = vec![0f32; w as usize * h as usize * n_channels];
+    for (img, weight) in imgs.iter().zip(weights) {
+        for (dst, pix) in accu.chunks_mut(n_channels).zip(img.iter()) {
+            for (a, c) in dst.iter_mut().zip(pix.channels()) {
+                *a += weight * <f32 as NumCast>::from(*c).unwrap();
+            }
+        }
+    }
+    let merged = accu
+        .chunks(n_channels)
+        .map(|channels| {
+            let channels = channels.iter().map(|c| c / total).collect::<Vec<f32>>();
+            Q::from_slice(&channels)
+        })
+        .collect();
+    ImageBuffer2D::from_vec(w, h, merged)
+}
+
+/// Blend two images using a grayscale mask as alpha channel: each output pixel is taken from `a` where the mask is 0,
+/// from `b` where it is 255, and is a proportional mix of both in between.
+///
+/// **Error**: if the dimensions of the three images do not match.
+pub fn masked_blend<P>(
+    a: &dyn Image2D<P>,
+    b: &dyn Image2D<P>,
+    mask: &dyn Image2D<Luma<u8>>,
+) -> Result<ImageBuffer2D<P>, Error>
+where
+    P: Pixel,
+{
+    if a.dimensions() != b.dimensions() || a.dimensions() != mask.dimensions() {
+        bail!("Image dimensions do not match");
+    }
+    let mut channels = Vec::with_capacity(P::N_CHANNELS as usize);
+    let blended = a
+        .iter()
+        .zip(b.iter())
+        .zip(mask.iter())
+        .map(|((pa, pb), m)| {
+            let alpha = m.data[0] as f32 / 255.;
+            channels.clear();
+            channels.extend(pa.channels().iter().zip(pb.channels()).map(|(ca, cb)| {
+                let ca = <f32 as NumCast>::from(*ca).unwrap();
+                let cb = <f32 as NumCast>::from(*cb).unwrap();
+                round_saturate::<P::Subpixel>(ca + alpha * (cb - ca))
+            }));
+            P::from_slice(&channels)
+        })
+        .collect();
+    ImageBuffer2D::from_vec(a.width(), a.height(), blended)
+}
+
+/// Compute the per-subpixel absolute difference `|a - b|` of two images, which never wraps around unlike the
+/// subtraction of integer pixels.
+///
+/// **Error**: if the dimensions of the images do not match.
+pub fn abs_diff<P>(a: &dyn Image2D<P>, b: &dyn Image2D<P>) -> Result<ImageBuffer2D<P>, Error>
+where
+    P: Pixel,
+{
+    if a.dimensions() != b.dimensions() {
+        bail!("Image dimensions do not match");
+    }
+    let mut channels = Vec::with_capacity(P::N_CHANNELS as usize);
+    let diff = a
+        .iter()
+        .zip(b.iter())
+        .map(|(pa, pb)| {
+            channels.clear();
+            channels.extend(pa.channels().iter().zip(pb.channels()).map(|(&ca, &cb)| {
+                if ca > cb {
+                    ca - cb
+                } else {
+                    cb - ca
+                }
+            }));
+            P::from_slice(&channels)
+        })
+        .collect();
+    ImageBuffer2D::from_vec(a.width(), a.height(), diff)
+}
+
+/// Compute the per-subpixel signed difference `a - b` of two images into an image with `i32` subpixels, such as
+/// `Rgb<i32>`. Floating point differences are rounded, and differences out of the `i32` range are saturated. The
+/// output pixel type `Q` must have the same number of channels as `P`.
+///
+/// **Error**: if the dimensions of the images or the channel counts do not match.
+pub fn signed_diff<P, Q>(a: &dyn Image2D<P>, b: &dyn Image2D<P>) -> Result<ImageBuffer2D<Q>, Error>
+where
+    P: Pixel,
+    Q: Pixel<Subpixel = i32>,
+{
+    ensure!(
+        P::N_CHANNELS == Q::N_CHANNELS,
+        "Input and output pixel types have different channel counts: {} != {}",
+        P::N_CHANNELS,
+        Q::N_CHANNELS
+    );
+    if a.dimensions() != b.dimensions() {
+        bail!("Image dimensions do not match");
+    }
+    let mut channels = Vec::with_capacity(P::N_CHANNELS as usize);
+    let diff = a
+        .iter()
+        .zip(b.iter())
+        .map(|(pa, pb)| {
+            channels.clear();
+            channels.extend(pa.channels().iter().zip(pb.channels()).map(|(ca, cb)| {
+                let ca = <f64 as NumCast>::from(*ca).unwrap();
+                let cb = <f64 as NumCast>::from(*cb).unwrap();
+                round_saturate_f64::<i32>(ca - cb)
+            }));
+            Q::from_slice(&channels)
+        })
+        .collect();
+    ImageBuffer2D::from_vec(a.width(), a.height(), diff)
+}
+
+// Maximum number of relaxation sweeps of the Poisson solver of `seamless_clone`.
+const POISSON_MAX_ITERATIONS: u32 = 10_000;
+
+// The Poisson solver stops once no pixel changes by more than this amount during a sweep.
+const POISSON_TOLERANCE: f32 = 1e-3;
+
+// Over-relaxation factor of the Poisson solver, speeding up the convergence of Gauss-Seidel iterations.
+const POISSON_RELAXATION: f32 = 1.9;
+
+/// Paste the pixels of `src` selected by `mask` into `dst` with Poisson image editing: instead of copying the pixels,
+/// the pasted region keeps the gradients of `src` while matching the pixels of `dst` around it, so that no seam is
+/// visible. The top-left corner of `src` is placed at `offset` in `dst`, and the selected pixels falling out of `dst`
+/// are ignored. The Poisson equation is solved with successive over-relaxation, whose cost grows with the area of the
+/// region.
+///
+/// **Error**: if the dimensions of `src` and `mask` do not match.
+pub fn seamless_clone(
+    src: &dyn Image2D<Rgb<u8>>,
+    dst: &dyn Image2D<Rgb<u8>>,
+    mask: &dyn Image2D<Luma<u8>>,
+    offset: (i64, i64),
+) -> Result<ImageBuffer2D<Rgb<u8>>, Error> {
+    if src.dimensions() != mask.dimensions() {
+        bail!(
+            "Source and mask dimensions do not match: {:?} vs {:?}",
+            src.dimensions(),
+            mask.dimensions()
+        );
+    }
+    let (dw, dh) = (dst.width() as i64, dst.height() as i64);
+    let (sw, sh) = (src.width() as i64, src.height() as i64);
+    let to_f32 = |p: &Rgb<u8>| [p.data[0] as f32, p.data[1] as f32, p.data[2] as f32];
+    let mut out = dst.iter().map(to_f32).collect::<Vec<[f32; 3]>>();
+
+    // Each pixel of the region, as its index in `dst`, its neighbors in `dst` and the sum of the gradients of `src`
+    // towards them.
+    let mut region = Vec::new();
+    for ((y, x), m) in mask.enumerate_pixels() {
+        let (x, y) = (x as i64, y as i64);
+        let (tx, ty) = (x + offset.0, y + offset.1);
+        if m.data[0] == 0 || tx < 0 || ty < 0 || tx >= dw || ty >= dh {
+            continue;
+        }
+        let value = to_f32(src.get_pixel(x as u32, y as u32));
+        let mut neighbors = Vec::with_capacity(4);
+        let mut guidance = [0.; 3];
+        for &(dx, dy) in &[(-1, 0), (1, 0), (0, -1), (0, 1)] {
+            let (nx, ny) = (tx + dx, ty + dy);
+            if nx < 0 || ny < 0 || nx >= dw || ny >= dh {
+                continue;
+            }
+            neighbors.push((ny * dw + nx) as usize);
+            let (sx, sy) = (x + dx, y + dy);
+            if sx >= 0 && sy >= 0 && sx < sw && sy < sh {
+                let other = to_f32(src.get_pixel(sx as u32, sy as u32));
+                for c in 0..3 {
+                    guidance[c] += value[c] - other[c];
+                }
+            }
+        }
+        let k = (ty * dw + tx) as usize;
+        if neighbors.is_empty() {
+            out[k] = value;
+        } else {
+            region.push((k, neighbors, guidance));
+        }
+    }
+
+    for _ in 0..POISSON_MAX_ITERATIONS {
+        let mut max_change = 0f32;
+        for &(k, ref neighbors, guidance) in &region {
+            let n = neighbors.len() as f32;
+            for c in 0..3 {
+                let sum = guidance[c] + neighbors.iter().map(|&q| out[q][c]).sum::<f32>();
+                let change = POISSON_RELAXATION * (sum / n - out[k][c]);
+                out[k][c] += change;
+                max_change = max_change.max(change.abs());
+            }
+        }
+        if max_change < POISSON_TOLERANCE {
+            break;
+        }
+    }
+
+    let pixels = out
+        .iter()
+        .map(|c| {
+            Rgb::new([
+                round_saturate(c[0]),
+                round_saturate(c[1]),
+                round_saturate(c[2]),
+            ])
+        })
+        .collect();
+    ImageBuffer2D::from_vec(dst.width(), dst.height(), pixels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::{Image2DMut, Luma, Rgb};
+
+    #[test]
+    fn test_weighted_merge() {
+        let img1 = ImageBuffer2D::generate(4, 3, |(x, y)| Luma::new([(x * 10 + y) as u8]));
+        let img2 = ImageBuffer2D::generate(4, 3, |(x, y)| Luma::new([(200 - x * y) as u8]));
+        let merged: ImageBuffer2D<Luma<f32>> =
+            weighted_merge(&[&img1, &img2], &[0.25, 0.75]).unwrap();
+        for ((p, p1), p2) in merged.iter().zip(img1.iter()).zip(img2.iter()) {
+            let expected = 0.25 * p1.data[0] as f32 + 0.75 * p2.data[0] as f32;
+            assert!((p.data[0] - expected).abs() < 1e-4);
+        }
+
+        let rgb = ImageBuffer2D::generate(2, 2, |(x, _y)| Rgb::new([x as u8, 10, 20]));
+        let merged: ImageBuffer2D<Rgb<f32>> = weighted_merge(&[&rgb, &rgb], &[1., 3.]).unwrap();
+        assert_eq!(merged.get_pixel(1, 0), &Rgb::new([1., 10., 20.]));
+    }
+
+    #[test]
+    fn test_weighted_merge_errors() {
+        let img1 = ImageBuffer2D::<Luma<u8>>::new(4, 3);
+        let img2 = ImageBuffer2D::<Luma<u8>>::new(3, 4);
+        assert!(weighted_merge::<_, Luma<f32>>(&[&img1, &img1], &[1.]).is_err());
+        assert!(weighted_merge::<_, Luma<f32>>(&[&img1, &img2], &[1., 1.]).is_err());
+        assert!(weighted_merge::<Luma<u8>, Luma<f32>>(&[], &[]).is_err());
+        assert!(weighted_merge::<_, Luma<f32>>(&[&img1, &img1], &[1., -1.]).is_err());
+        assert!(weighted_merge::<_, Rgb<f32>>(&[&img1], &[1.]).is_err());
+    }
+
+    #[test]
+    fn test_masked_blend() {
+        let a = ImageBuffer2D::generate(6, 4, |(x, y)| Rgb::new([x as u8, y as u8, 1]));
+        let b =
+            ImageBuffer2D::generate(6, 4, |(x, y)| Rgb::new([100 + x as u8, 100 + y as u8, 200]));
+        let mask =
+            ImageBuffer2D::generate(6, 4, |(x, _y)| Luma::new([if x < 3 { 0 } else { 255 }]));
+        let blended = masked_blend(&a, &b, &mask).unwrap();
+        for ((y, x), p) in blended.enumerate_pixels() {
+            let (x, y) = (x as u32, y as u32);
+            let expected = if x < 3 {
+                a.get_pixel(x, y)
+            } else {
+                b.get_pixel(x, y)
+            };
+            assert_eq!(p, expected);
+        }
+
+        let half = ImageBuffer2D::generate(6, 4, |_| Luma::new([128u8]));
+        let blended = masked_blend(&a, &b, &half).unwrap();
+        assert_eq!(blended.get_pixel(0, 0), &Rgb::new([50, 50, 101]));
+
+        let small = ImageBuffer2D::<Luma<u8>>::new(3, 4);
+        assert!(masked_blend(&a, &b, &small).is_err());
+    }
+
+    #[test]
+    fn test_abs_diff() {
+        let a = ImageBuffer2D::generate(3, 2, |(x, _)| Rgb::new([10u8, 250, x as u8]));
+        let b = ImageBuffer2D::generate(3, 2, |(x, _)| Rgb::new([250u8, 10, 2 * x as u8]));
+        let diff = abs_diff(&a, &b).unwrap();
+        assert_eq!(diff.get_pixel(0, 0), &Rgb::new([240, 240, 0]));
+        assert_eq!(diff.get_pixel(2, 1), &Rgb::new([240, 240, 2]));
+        assert_eq!(abs_diff(&b, &a).unwrap(), diff);
+
+        let small = ImageBuffer2D::<Rgb<u8>>::new(2, 2);
+        assert!(abs_diff(&a, &small).is_err());
+    }
+
+    #[test]
+    fn test_signed_diff() {
+        let a = ImageBuffer2D::generate(3, 2, |(x, _)| Rgb::new([10u8, 250, x as u8]));
+        let b = ImageBuffer2D::generate(3, 2, |(x, _)| Rgb::new([250u8, 10, 2 * x as u8]));
+        let diff: ImageBuffer2D<Rgb<i32>> = signed_diff(&a, &b).unwrap();
+        assert_eq!(diff.get_pixel(2, 0), &Rgb::new([-240, 240, -2]));
+
+        let a = ImageBuffer2D::generate(2, 2, |_| Luma::new([1.6f32]));
+        let b = ImageBuffer2D::generate(2, 2, |_| Luma::new([0.2f32]));
+        let diff: ImageBuffer2D<Luma<i32>> = signed_diff(&a, &b).unwrap();
+        assert_eq!(diff.get_pixel(1, 1), &Luma::new([1]));
+
+        let small = ImageBuffer2D::<Luma<f32>>::new(2, 1);
+        assert!(signed_diff::<_, Luma<i32>>(&a, &small).is_err());
+        assert!(signed_diff::<_, Rgb<i32>>(&a, &b).is_err());
+    }
+
+    #[test]
+    fn test_seamless_clone() {
+        let dst = ImageBuffer2D::generate(40, 40, |_| Rgb::new([100u8, 120, 140]));
+        // A bright patch with a darker cross in its middle.
+        let src = ImageBuffer2D::generate(16, 16, |(x, y)| {
+            if x == 8 || y == 8 {
+                Rgb::new([150u8, 150, 150])
+            } else {
+                Rgb::new([220u8, 200, 180])
+            }
+        });
+        let mask = ImageBuffer2D::generate(16, 16, |(x, y)| {
+            Luma::new([if (3..13).contains(&x) && (3..13).contains(&y) {
+                255u8
+            } else {
+                0
+            }])
+        });
+        let offset = (10, 12);
+        let cloned = seamless_clone(&src, &dst, &mask, offset).unwrap();
+        let mut blit = dst.to_owned();
+        for ((y, x), m) in mask.enumerate_pixels() {
+            if m.data[0] != 0 {
+                let (x, y) = (x as u32, y as u32);
+                blit.put_pixel(x + 10, y + 12, *src.get_pixel(x, y));
+            }
+        }
+
+        // Sum of the absolute differences across the boundary of the pasted region.
+        let seam = |img: &ImageBuffer2D<Rgb<u8>>| {
+            let mut total = 0;
+            for i in 0..10 {
+                let pairs = [
+                    ((13, 15 + i), (12, 15 + i)),
+                    ((22, 15 + i), (23, 15 + i)),
+                    ((13 + i, 15), (13 + i, 14)),
+                    ((13 + i, 24), (13 + i, 25)),
+                ];
+                for &((x0, y0), (x1, y1)) in &pairs {
+                    let (p, q) = (img.get_pixel(x0, y0), img.get_pixel(x1, y1));
+                    for c in 0..3 {
+                        total += (p.data[c] as i32 - q.data[c] as i32).abs();
+                    }
+                }
+            }
+            total
+        };
+        assert!(
+            seam(&cloned) * 10 < seam(&blit),
+            "{} vs {}",
+            seam(&cloned),
+            seam(&blit)
+        );
+
+        // Pixels outside of the region are untouched, and the cross remains visible inside.
+        assert_eq!(cloned.get_pixel(12, 20), dst.get_pixel(12, 20));
+        assert_eq!(cloned.get_pixel(30, 30), dst.get_pixel(30, 30));
+        let (on, off) = (cloned.get_pixel(18, 17), cloned.get_pixel(16, 17));
+        assert!(
+            off.data[0] as i32 - on.data[0] as i32 > 50,
+            "{:?} vs {:?}",
+            on,
+            off
+        );
+
+        // Regions partially out of the destination are cropped.
+        let cropped = seamless_clone(&src, &dst, &mask, (-5, 30)).unwrap();
+        assert_eq!(cropped.dimensions(), (40, 40));
+
+        let small = ImageBuffer2D::<Luma<u8>>::new(15, 16);
+        assert!(seamless_clone(&src, &dst, &small, offset).is_err());
+    }
+}
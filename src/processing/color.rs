@@ -0,0 +1,594 @@
+//! Contains color balance adjustments and tone effects.
+
+use core::{Image2D, Image2DMut, ImageBuffer2D, Lab, Luma, Pixel, Rgb, Xyz};
+use helper::generic::{round_saturate, srgb_decode, srgb_encode};
+use processing::map::map_pixels;
+
+use failure::Error;
+
+/// Balance the colors of an image under the gray-world assumption: each channel is scaled so that the means of the
+/// three channels become equal to their common average. Channels whose mean is zero are left unchanged.
+pub fn gray_world_white_balance(img: &dyn Image2D<Rgb<u8>>) -> ImageBuffer2D<Rgb<u8>> {
+    let n = f64::from(img.width()) * f64::from(img.height());
+    if n == 0. {
+        return img.to_owned();
+    }
+    let mut means = [0f64; 3];
+    for p in img.iter() {
+        for (m, c) in means.iter_mut().zip(&p.data) {
+            *m += f64::from(*c);
+        }
+    }
+    means.iter_mut().for_each(|m| *m /= n);
+    let gray = means.iter().sum::<f64>() / 3.;
+    let mut scales = [1f32; 3];
+    for (s, m) in scales.iter_mut().zip(&means) {
+        if *m > 0. {
+            *s = (gray / m) as f32;
+        }
+    }
+
+    let mut out = img.to_owned();
+    for p in out.iter_mut() {
+        for (c, s) in p.data.iter_mut().zip(&scales) {
+            *c = round_saturate(f32::from(*c) * s);
+        }
+    }
+    out
+}
+
+// Map every subpixel of an image through a lookup table.
+fn map_lut<P>(img: &dyn Image2D<P>, lut: &[u8; 256]) -> ImageBuffer2D<P>
+where
+    P: Pixel<Subpixel = u8>,
+{
+    let mut out = img.to_owned();
+    for p in out.iter_mut() {
+        for c in p.channels_mut() {
+            *c = lut[*c as usize];
+        }
+    }
+    out
+}
+
+/// Reduce each channel of an 8-bit image to its `bits` most significant bits, re-expanded to the full range, for a
+/// posterized look.
+///
+/// **Panics** if `bits` is not in `1..=8`.
+pub fn posterize<P>(img: &dyn Image2D<P>, bits: u8) -> ImageBuffer2D<P>
+where
+    P: Pixel<Subpixel = u8>,
+{
+    assert!((1..=8).contains(&bits), "Invalid bit depth: {}", bits);
+    let levels = (1u32 << bits) - 1;
+    let mut lut = [0u8; 256];
+    for (i, v) in lut.iter_mut().enumerate() {
+        *v = ((i as u32 >> (8 - bits)) * 255 / levels) as u8;
+    }
+    map_lut(img, &lut)
+}
+
+/// Solarize an 8-bit image: each channel value above `threshold` is inverted, the others are left unchanged.
+pub fn solarize<P>(img: &dyn Image2D<P>, threshold: u8) -> ImageBuffer2D<P>
+where
+    P: Pixel<Subpixel = u8>,
+{
+    let mut lut = [0u8; 256];
+    for (i, v) in lut.iter_mut().enumerate() {
+        let i = i as u8;
+        *v = if i > threshold { 255 - i } else { i };
+    }
+    map_lut(img, &lut)
+}
+
+/// Apply a different gamma to each channel of an 8-bit RGB image, mapping each normalized channel value `v` to
+/// `v^gamma` through a lookup table. Gammas smaller than 1 brighten the channel and larger ones darken it, which allows
+/// simple color grading.
+///
+/// **Panics** if a gamma is not strictly positive and finite.
+pub fn adjust_gamma_per_channel(
+    img: &dyn Image2D<Rgb<u8>>,
+    gammas: [f32; 3],
+) -> ImageBuffer2D<Rgb<u8>> {
+    assert!(
+        gammas.iter().all(|g| g.is_finite() && *g > 0.),
+        "Invalid gammas: {:?}",
+        gammas
+    );
+    let mut luts = [[0u8; 256]; 3];
+    for (lut, gamma) in luts.iter_mut().zip(&gammas) {
+        for (i, v) in lut.iter_mut().enumerate() {
+            *v = round_saturate((i as f32 / 255.).powf(*gamma) * 255.);
+        }
+    }
+    map_pixels(img, |p| {
+        Rgb::new([
+            luts[0][p.data[0] as usize],
+            luts[1][p.data[1] as usize],
+            luts[2][p.data[2] as usize],
+        ])
+    })
+}
+
+/// Apply a sepia tone to an image, using the standard sepia color matrix. Values are saturated to the range of `u8`.
+pub fn sepia(img: &dyn Image2D<Rgb<u8>>) -> ImageBuffer2D<Rgb<u8>> {
+    const SEPIA: [[f32; 3]; 3] = [
+        [0.393, 0.769, 0.189],
+        [0.349, 0.686, 0.168],
+        [0.272, 0.534, 0.131],
+    ];
+    color_matrix(img, SEPIA, [0.; 3])
+}
+
+/// Transform the colors of an image by the affine map `out = m * in + bias`, applied to each pixel as a column vector.
+/// This covers channel mixing, hue rotations and many color effects. Values are saturated to the range of `u8`.
+pub fn color_matrix(
+    img: &dyn Image2D<Rgb<u8>>,
+    m: [[f32; 3]; 3],
+    bias: [f32; 3],
+) -> ImageBuffer2D<Rgb<u8>> {
+    let mut out = img.to_owned();
+    for p in out.iter_mut() {
+        let input = [
+            f32::from(p.data[0]),
+            f32::from(p.data[1]),
+            f32::from(p.data[2]),
+        ];
+        for ((c, row), b) in p.data.iter_mut().zip(&m).zip(&bias) {
+            *c = round_saturate(row[0] * input[0] + row[1] * input[1] + row[2] * input[2] + b);
+        }
+    }
+    out
+}
+
+// Linear RGB to XYZ matrix for the sRGB primaries and the D65 white point.
+const RGB_TO_XYZ: [[f32; 3]; 3] = [
+    [0.412_456_4, 0.357_576_1, 0.180_437_5],
+    [0.212_672_9, 0.715_152_2, 0.072_175],
+    [0.019_333_9, 0.119_192, 0.950_304_1],
+];
+
+// Inverse of `RGB_TO_XYZ`.
+const XYZ_TO_RGB: [[f32; 3]; 3] = [
+    [3.240_454_2, -1.537_138_5, -0.498_531_4],
+    [-0.969_266, 1.876_010_8, 0.041_556],
+    [0.055_643_4, -0.204_025_9, 1.057_225_2],
+];
+
+// XYZ coordinates of the D65 white point.
+const D65: [f32; 3] = [0.950_47, 1., 1.088_83];
+
+// Multiply a 3-vector by a 3x3 matrix.
+fn mat_vec(m: &[[f32; 3]; 3], v: [f32; 3]) -> [f32; 3] {
+    let mut out = [0.; 3];
+    for (o, row) in out.iter_mut().zip(m) {
+        *o = row[0] * v[0] + row[1] * v[1] + row[2] * v[2];
+    }
+    out
+}
+
+/// Convert an sRGB image to linear light with the exact piecewise sRGB transfer function, normalizing the channels to
+/// `[0, 1]`. Blending and resampling are only physically correct in linear light.
+pub fn srgb_to_linear(img: &dyn Image2D<Rgb<u8>>) -> ImageBuffer2D<Rgb<f32>> {
+    map_pixels(img, |p| {
+        let mut data = [0.; 3];
+        for (l, c) in data.iter_mut().zip(&p.data) {
+            *l = srgb_decode(f32::from(*c) / 255.);
+        }
+        Rgb::new(data)
+    })
+}
+
+/// Convert a linear light image with channels in `[0, 1]` to sRGB with the exact piecewise sRGB transfer function.
+/// This is the inverse of `srgb_to_linear`, and values out of range are saturated.
+pub fn linear_to_srgb(img: &dyn Image2D<Rgb<f32>>) -> ImageBuffer2D<Rgb<u8>> {
+    map_pixels(img, |p| {
+        let mut data = [0; 3];
+        for (c, l) in data.iter_mut().zip(&p.data) {
+            *c = round_saturate(srgb_encode(l.max(0.)) * 255.);
+        }
+        Rgb::new(data)
+    })
+}
+
+/// Convert an sRGB image to the CIE XYZ color space, using the D65 white point.
+pub fn rgb_to_xyz(img: &dyn Image2D<Rgb<u8>>) -> ImageBuffer2D<Xyz<f32>> {
+    map_pixels(&srgb_to_linear(img), |p| {
+        Xyz::new(mat_vec(&RGB_TO_XYZ, p.data))
+    })
+}
+
+/// Convert a CIE XYZ image to sRGB, using the D65 white point. Colors out of the sRGB gamut are saturated.
+pub fn xyz_to_rgb(img: &dyn Image2D<Xyz<f32>>) -> ImageBuffer2D<Rgb<u8>> {
+    linear_to_srgb(&map_pixels(img, |p| Rgb::new(mat_vec(&XYZ_TO_RGB, p.data))))
+}
+
+/// Convert a CIE XYZ image to CIE L\*a\*b\*, relative to the D65 white point.
+pub fn xyz_to_lab(img: &dyn Image2D<Xyz<f32>>) -> ImageBuffer2D<Lab<f32>> {
+    const DELTA: f32 = 6. / 29.;
+    let f = |t: f32| {
+        if t > DELTA * DELTA * DELTA {
+            t.cbrt()
+        } else {
+            t / (3. * DELTA * DELTA) + 4. / 29.
+        }
+    };
+    map_pixels(img, |p| {
+        let fx = f(p.data[0] / D65[0]);
+        let fy = f(p.data[1] / D65[1]);
+        let fz = f(p.data[2] / D65[2]);
+        Lab::new([116. * fy - 16., 500. * (fx - fy), 200. * (fy - fz)])
+    })
+}
+
+/// Convert a CIE L\*a\*b\* image to CIE XYZ, relative to the D65 white point.
+pub fn lab_to_xyz(img: &dyn Image2D<Lab<f32>>) -> ImageBuffer2D<Xyz<f32>> {
+    const DELTA: f32 = 6. / 29.;
+    let f_inv = |t: f32| {
+        if t > DELTA {
+            t * t * t
+        } else {
+            3. * DELTA * DELTA * (t - 4. / 29.)
+        }
+    };
+    map_pixels(img, |p| {
+        let fy = (p.data[0] + 16.) / 116.;
+        let fx = fy + p.data[1] / 500.;
+        let fz = fy - p.data[2] / 200.;
+        Xyz::new([D65[0] * f_inv(fx), D65[1] * f_inv(fy), D65[2] * f_inv(fz)])
+    })
+}
+
+/// Convert an sRGB image to CIE L\*a\*b\*, relative to the D65 white point.
+pub fn rgb_to_lab(img: &dyn Image2D<Rgb<u8>>) -> ImageBuffer2D<Lab<f32>> {
+    xyz_to_lab(&rgb_to_xyz(img))
+}
+
+/// Convert a CIE L\*a\*b\* image to sRGB, relative to the D65 white point. Colors out of the sRGB gamut are
+/// saturated.
+pub fn lab_to_rgb(img: &dyn Image2D<Lab<f32>>) -> ImageBuffer2D<Rgb<u8>> {
+    xyz_to_rgb(&lab_to_xyz(img))
+}
+
+/// Compute the CIE76 color difference between two sRGB images, i.e. the euclidean distance between their pixels in the
+/// CIE L\*a\*b\* color space. A difference of about 2.3 is just noticeable.
+///
+/// Fails if the images do not have the same dimensions.
+pub fn delta_e(
+    a: &dyn Image2D<Rgb<u8>>,
+    b: &dyn Image2D<Rgb<u8>>,
+) -> Result<ImageBuffer2D<Luma<f32>>, Error> {
+    if a.dimensions() != b.dimensions() {
+        bail!(
+            "Image dimensions do not match: {:?} vs {:?}",
+            a.dimensions(),
+            b.dimensions()
+        );
+    }
+    let (lab_a, lab_b) = (rgb_to_lab(a), rgb_to_lab(b));
+    let diff = lab_a
+        .iter()
+        .zip(lab_b.iter())
+        .map(|(p, q)| {
+            let d2 = p
+                .data
+                .iter()
+                .zip(&q.data)
+                .map(|(x, y)| (x - y) * (x - y))
+                .sum::<f32>();
+            Luma::new([d2.sqrt()])
+        })
+        .collect();
+    ImageBuffer2D::from_vec(a.width(), a.height(), diff)
+}
+
+/// Convert an sRGB image to planar Y'CbCr with 4:2:0 chroma subsampling, as used by most video codecs. The
+/// conversion uses the full-range BT.601 coefficients of JPEG/JFIF. The luma plane has the dimensions of the image,
+/// while each chroma plane stores the average of each 2x2 block, its dimensions being rounded up for odd image
+/// dimensions.
+///
+/// Returns the Y, U (Cb) and V (Cr) planes, in row-major order.
+pub fn rgb_to_yuv420(img: &dyn Image2D<Rgb<u8>>) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+    let (w, h) = img.dimensions();
+    let (cw, ch) = (w.div_ceil(2), h.div_ceil(2));
+    let mut y_plane = Vec::with_capacity((w * h) as usize);
+    let mut u_sum = vec![0f32; (cw * ch) as usize];
+    let mut v_sum = vec![0f32; (cw * ch) as usize];
+    let mut counts = vec![0f32; (cw * ch) as usize];
+    for ((y, x), p) in img.enumerate_pixels() {
+        let [r, g, b] = [
+            f32::from(p.data[0]),
+            f32::from(p.data[1]),
+            f32::from(p.data[2]),
+        ];
+        y_plane.push(round_saturate(0.299 * r + 0.587 * g + 0.114 * b));
+        let i = (y / 2) * cw as usize + x / 2;
+        u_sum[i] += -0.168_736 * r - 0.331_264 * g + 0.5 * b;
+        v_sum[i] += 0.5 * r - 0.418_688 * g - 0.081_312 * b;
+        counts[i] += 1.;
+    }
+    let average = |sums: Vec<f32>| {
+        sums.iter()
+            .zip(&counts)
+            .map(|(s, n)| round_saturate(s / n + 128.))
+            .collect::<Vec<u8>>()
+    };
+    (y_plane, average(u_sum), average(v_sum))
+}
+
+/// Convert planar Y'CbCr with 4:2:0 chroma subsampling, as produced by `rgb_to_yuv420`, back to a `width x height`
+/// sRGB image. Each chroma sample is shared by the pixels of its 2x2 block.
+///
+/// **Error**: if the length of a plane does not match the dimensions.
+pub fn yuv420_to_rgb(
+    y: &[u8],
+    u: &[u8],
+    v: &[u8],
+    width: u32,
+    height: u32,
+) -> Result<ImageBuffer2D<Rgb<u8>>, Error> {
+    let (w, h) = (width as usize, height as usize);
+    let (cw, ch) = (w.div_ceil(2), h.div_ceil(2));
+    ensure!(
+        y.len() == w * h,
+        "Luma plane length {} does not match the dimensions ({}, {})",
+        y.len(),
+        width,
+        height
+    );
+    ensure!(
+        u.len() == cw * ch && v.len() == cw * ch,
+        "Chroma plane lengths {} and {} do not match the subsampled dimensions ({}, {})",
+        u.len(),
+        v.len(),
+        cw,
+        ch
+    );
+    Ok(ImageBuffer2D::generate(width, height, |(x, row)| {
+        let (x, row) = (x as usize, row as usize);
+        let i = (row / 2) * cw + x / 2;
+        let luma = f32::from(y[row * w + x]);
+        let (cb, cr) = (f32::from(u[i]) - 128., f32::from(v[i]) - 128.);
+        Rgb::new([
+            round_saturate(luma + 1.402 * cr),
+            round_saturate(luma - 0.344_136 * cb - 0.714_136 * cr),
+            round_saturate(luma + 1.772 * cb),
+        ])
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::Luma;
+
+    fn channel_means(img: &ImageBuffer2D<Rgb<u8>>) -> [f32; 3] {
+        let n = (img.width() * img.height()) as f32;
+        let mut means = [0.; 3];
+        for p in img.iter() {
+            for (m, c) in means.iter_mut().zip(&p.data) {
+                *m += f32::from(*c) / n;
+            }
+        }
+        means
+    }
+
+    #[test]
+    fn test_gray_world_white_balance() {
+        let img = ImageBuffer2D::generate(16, 16, |(x, y)| {
+            let v = ((x * 13 + y * 7) % 150) as f32;
+            Rgb::new([(v * 1.5 + 20.) as u8, v as u8, (v * 0.9) as u8])
+        });
+        let means = channel_means(&img);
+        assert!(means[0] > means[1] + 20.);
+
+        let balanced = gray_world_white_balance(&img);
+        let means = channel_means(&balanced);
+        assert!((means[0] - means[1]).abs() < 1.);
+        assert!((means[1] - means[2]).abs() < 1.);
+    }
+
+    #[test]
+    fn test_posterize() {
+        let img = ImageBuffer2D::generate(16, 16, |(x, y)| Luma::new([(y * 16 + x) as u8]));
+        let binary = posterize(&img, 1);
+        assert!(binary.iter().all(|p| p.data[0] == 0 || p.data[0] == 255));
+        assert_eq!(binary.get_pixel(15, 7), &Luma::new([0]));
+        assert_eq!(binary.get_pixel(0, 8), &Luma::new([255]));
+        assert_eq!(posterize(&img, 8), img);
+
+        let rgb = ImageBuffer2D::generate(4, 4, |(x, y)| {
+            Rgb::new([(x * 60) as u8, (y * 70) as u8, 255])
+        });
+        let quantized = posterize(&rgb, 2);
+        for p in quantized.iter() {
+            assert!(p.data.iter().all(|c| [0, 85, 170, 255].contains(c)));
+        }
+    }
+
+    #[test]
+    fn test_solarize() {
+        let img =
+            ImageBuffer2D::generate(4, 2, |(_x, y)| Luma::new([if y == 0 { 200 } else { 50 }]));
+        let solarized = solarize(&img, 128);
+        for ((y, _x), p) in solarized.enumerate_pixels() {
+            assert_eq!(p, &Luma::new([if y == 0 { 55 } else { 50 }]));
+        }
+
+        let rgb = ImageBuffer2D::generate(1, 1, |_| Rgb::new([200, 128, 129]));
+        assert_eq!(
+            solarize(&rgb, 128).get_pixel(0, 0),
+            &Rgb::new([55, 128, 126])
+        );
+    }
+
+    #[test]
+    fn test_sepia() {
+        let white = ImageBuffer2D::generate(2, 2, |_| Rgb::new([255u8, 255, 255]));
+        assert!(sepia(&white)
+            .iter()
+            .all(|p| p == &Rgb::new([255, 255, 239])));
+
+        let img = ImageBuffer2D::generate(16, 16, |(x, y)| {
+            Rgb::new([(x * 16) as u8, (y * 16) as u8, ((x * y) % 256) as u8])
+        });
+        for p in sepia(&img).iter() {
+            assert!(p.data[0] >= p.data[1] && p.data[1] >= p.data[2], "{:?}", p);
+        }
+    }
+
+    #[test]
+    fn test_color_matrix() {
+        let img = ImageBuffer2D::generate(8, 8, |(x, y)| {
+            Rgb::new([(x * 30) as u8, (y * 30) as u8, ((x + y) * 15) as u8])
+        });
+        let identity = [[1., 0., 0.], [0., 1., 0.], [0., 0., 1.]];
+        assert_eq!(color_matrix(&img, identity, [0.; 3]), img);
+
+        // A hue rotation by 120 degrees cycles the channels.
+        let rotation = [[0., 0., 1.], [1., 0., 0.], [0., 1., 0.]];
+        let rotated = color_matrix(&img, rotation, [0.; 3]);
+        for (p, q) in rotated.iter().zip(img.iter()) {
+            assert_eq!(p, &Rgb::new([q.data[2], q.data[0], q.data[1]]));
+        }
+
+        let shifted = color_matrix(&img, identity, [10., -300., 0.4]);
+        assert_eq!(shifted.get_pixel(0, 0), &Rgb::new([10, 0, 0]));
+        assert_eq!(shifted.get_pixel(7, 7), &Rgb::new([220, 0, 210]));
+    }
+
+    #[test]
+    fn test_lab_conversions() {
+        let white = ImageBuffer2D::generate(1, 1, |_| Rgb::new([255u8; 3]));
+        let xyz = rgb_to_xyz(&white);
+        let expected = [0.950_47, 1., 1.088_83];
+        for (c, e) in xyz.get_pixel(0, 0).data.iter().zip(&expected) {
+            assert!((c - e).abs() < 1e-3, "{:?}", xyz.get_pixel(0, 0));
+        }
+        let lab = rgb_to_lab(&white).get_pixel(0, 0).data;
+        assert!((lab[0] - 100.).abs() < 1e-2 && lab[1].abs() < 1e-2 && lab[2].abs() < 1e-2);
+        let black = ImageBuffer2D::generate(1, 1, |_| Rgb::new([0u8; 3]));
+        assert!(rgb_to_lab(&black).get_pixel(0, 0).data[0].abs() < 1e-3);
+
+        let img = ImageBuffer2D::generate(16, 16, |(x, y)| {
+            Rgb::new([(x * 17) as u8, (y * 17) as u8, ((x * y) % 256) as u8])
+        });
+        assert_eq!(lab_to_rgb(&rgb_to_lab(&img)), img);
+        assert_eq!(xyz_to_rgb(&rgb_to_xyz(&img)), img);
+    }
+
+    #[test]
+    fn test_delta_e() {
+        let img = ImageBuffer2D::generate(8, 8, |(x, y)| {
+            Rgb::new([(x * 30) as u8, (y * 30) as u8, 128])
+        });
+        assert!(delta_e(&img, &img).unwrap().iter().all(|p| p.data[0] == 0.));
+
+        // Black and white differ only by their lightness.
+        let white = ImageBuffer2D::generate(2, 1, |_| Rgb::new([255u8; 3]));
+        let black = ImageBuffer2D::generate(2, 1, |_| Rgb::new([0u8; 3]));
+        for p in delta_e(&white, &black).unwrap().iter() {
+            assert!((p.data[0] - 100.).abs() < 1e-2, "{:?}", p);
+        }
+        // Pure red is at L*a*b* (53.24, 80.09, 67.20).
+        let red = ImageBuffer2D::generate(2, 1, |_| Rgb::new([255u8, 0, 0]));
+        let expected = (46.76f32.powi(2) + 80.09f32.powi(2) + 67.2f32.powi(2)).sqrt();
+        for p in delta_e(&red, &white).unwrap().iter() {
+            assert!((p.data[0] - expected).abs() < 0.1, "{:?}", p);
+        }
+
+        assert!(delta_e(&img, &white).is_err());
+    }
+
+    #[test]
+    fn test_yuv420() {
+        let img = ImageBuffer2D::generate(7, 5, |(x, y)| {
+            Rgb::new([
+                (30 + 8 * x) as u8,
+                (200 - 6 * y) as u8,
+                (90 + 2 * x * y) as u8,
+            ])
+        });
+        let (y, u, v) = rgb_to_yuv420(&img);
+        assert_eq!((y.len(), u.len(), v.len()), (35, 12, 12));
+        let gray = ImageBuffer2D::generate(2, 2, |_| Rgb::new([100u8, 100, 100]));
+        assert_eq!(rgb_to_yuv420(&gray), (vec![100; 4], vec![128], vec![128]));
+
+        // A smooth image is reconstructed with small errors.
+        let back = yuv420_to_rgb(&y, &u, &v, 7, 5).unwrap();
+        assert_eq!(back.dimensions(), (7, 5));
+        for (p, q) in img.iter().zip(back.iter()) {
+            for (a, b) in p.data.iter().zip(&q.data) {
+                assert!(
+                    (i32::from(*a) - i32::from(*b)).abs() <= 10,
+                    "{:?} vs {:?}",
+                    p,
+                    q
+                );
+            }
+        }
+        // Uniform 2x2 blocks are reconstructed almost exactly.
+        let blocks = ImageBuffer2D::generate(6, 4, |(x, y)| {
+            Rgb::new([(40 * (x / 2)) as u8, 250 - (60 * (y / 2)) as u8, 77])
+        });
+        let (y, u, v) = rgb_to_yuv420(&blocks);
+        let back = yuv420_to_rgb(&y, &u, &v, 6, 4).unwrap();
+        for (p, q) in blocks.iter().zip(back.iter()) {
+            for (a, b) in p.data.iter().zip(&q.data) {
+                assert!(
+                    (i32::from(*a) - i32::from(*b)).abs() <= 2,
+                    "{:?} vs {:?}",
+                    p,
+                    q
+                );
+            }
+        }
+
+        assert!(yuv420_to_rgb(&y, &u, &v, 6, 5).is_err());
+        assert!(yuv420_to_rgb(&y, &u[1..], &v, 6, 4).is_err());
+    }
+
+    #[test]
+    fn test_srgb_linear() {
+        let img = ImageBuffer2D::generate(16, 16, |(x, y)| {
+            let v = (y * 16 + x) as u8;
+            Rgb::new([v, 255 - v, v / 2])
+        });
+        let linear = srgb_to_linear(&img);
+        assert_eq!(linear_to_srgb(&linear), img);
+
+        let gray = ImageBuffer2D::generate(1, 1, |_| Rgb::new([188u8, 0, 255]));
+        let linear = srgb_to_linear(&gray);
+        let p = linear.get_pixel(0, 0);
+        assert!((p.data[0] - 0.5029).abs() < 1e-4, "{}", p.data[0]);
+        assert_eq!(&p.data[1..], &[0., 1.]);
+        // The linear segment near black.
+        let dark = srgb_to_linear(&ImageBuffer2D::generate(1, 1, |_| Rgb::new([10u8, 10, 10])));
+        assert!((dark.get_pixel(0, 0).data[0] - 10. / 255. / 12.92).abs() < 1e-7);
+
+        let out_of_range = ImageBuffer2D::generate(1, 1, |_| Rgb::new([-0.5f32, 0.5, 2.]));
+        assert_eq!(
+            linear_to_srgb(&out_of_range).get_pixel(0, 0),
+            &Rgb::new([0, 188, 255])
+        );
+    }
+
+    #[test]
+    fn test_adjust_gamma_per_channel() {
+        let img = ImageBuffer2D::generate(16, 16, |(x, y)| {
+            let v = (y * 16 + x) as u8;
+            Rgb::new([v, 255 - v, v ^ 0x55])
+        });
+        assert_eq!(adjust_gamma_per_channel(&img, [1., 1., 1.]), img);
+
+        let graded = adjust_gamma_per_channel(&img, [2.2, 1., 1.]);
+        for (p, q) in img.iter().zip(graded.iter()) {
+            assert_eq!(&p.data[1..], &q.data[1..]);
+            assert!(q.data[0] <= p.data[0]);
+        }
+        // 128 / 255 squared is close to a quarter.
+        let graded = adjust_gamma_per_channel(&img, [2., 0.5, 1.]);
+        assert_eq!(graded.get_pixel(0, 8), &Rgb::new([64, 180, 128 ^ 0x55]));
+        assert_eq!(graded.get_pixel(15, 15).data[0], 255);
+    }
+}
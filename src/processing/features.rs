@@ -0,0 +1,214 @@
+//! Contains feature descriptors computed from images.
+
+use core::{Image2D, Luma};
+use processing::filter::gradients;
+
+use std::f32::consts::PI;
+
+// Regularization term of the block normalization, avoiding divisions by zero in uniform areas.
+const HOG_EPSILON: f32 = 1e-3;
+
+// Maximum number of iterations of the corner refinement.
+const CORNER_MAX_ITERATIONS: u32 = 20;
+
+// The corner refinement stops once an iteration moves the estimate by less than this distance, in pixels.
+const CORNER_EPSILON: f32 = 0.01;
+
+/// Compute the Histogram of Oriented Gradients descriptor of a grayscale image.
+///
+/// The image is divided into `cell x cell` cells, pixels of the right and bottom borders that do not fill a whole cell
+/// being ignored. Each cell accumulates the gradient magnitudes of its pixels into a histogram of `bins` unsigned
+/// orientations, bin `i` being centered on the angle `i * PI / bins` measured from the x axis towards the y axis, with
+/// linear interpolation between the two closest bins. The cells are then grouped in overlapping blocks of
+/// `block x block` cells, with a stride of one cell, and the histograms of each block are L2-normalized.
+///
+/// Returns the concatenation of the normalized blocks in scanline order, each block containing the histograms of its
+/// cells in scanline order. The descriptor is empty if the image contains less than `block x block` cells.
+///
+/// Note that the orientation of the gradient is perpendicular to the edges of the image.
+///
+/// **Panics** if `cell`, `bins` or `block` is zero.
+pub fn hog(img: &dyn Image2D<Luma<u8>>, cell: u32, bins: u32, block: u32) -> Vec<f32> {
+    assert!(
+        cell > 0 && bins > 0 && block > 0,
+        "The cell size, the number of bins and the block size must be strictly positive."
+    );
+    let (cells_x, cells_y) = (
+        (img.width() / cell) as usize,
+        (img.height() / cell) as usize,
+    );
+    let (bins, block) = (bins as usize, block as usize);
+    if cells_x < block || cells_y < block {
+        return Vec::new();
+    }
+
+    let (gx, gy) = gradients(img);
+    let mut histograms = vec![0f32; cells_x * cells_y * bins];
+    for y in 0..cells_y as u32 * cell {
+        for x in 0..cells_x as u32 * cell {
+            let (dx, dy) = (gx.get_pixel(x, y).data[0], gy.get_pixel(x, y).data[0]);
+            let magnitude = (dx * dx + dy * dy).sqrt();
+            if magnitude == 0. {
+                continue;
+            }
+            let position = dy.atan2(dx).rem_euclid(PI) * bins as f32 / PI;
+            let lower = position.floor();
+            let fraction = position - lower;
+            let offset = ((y / cell) as usize * cells_x + (x / cell) as usize) * bins;
+            let lower = lower as usize % bins;
+            histograms[offset + lower] += magnitude * (1. - fraction);
+            histograms[offset + (lower + 1) % bins] += magnitude * fraction;
+        }
+    }
+
+    let mut descriptor =
+        Vec::with_capacity((cells_x - block + 1) * (cells_y - block + 1) * block * block * bins);
+    for by in 0..=cells_y - block {
+        for bx in 0..=cells_x - block {
+            let start = descriptor.len();
+            for cy in by..by + block {
+                let offset = (cy * cells_x + bx) * bins;
+                descriptor.extend_from_slice(&histograms[offset..offset + block * bins]);
+            }
+            let norm = (descriptor[start..].iter().map(|v| v * v).sum::<f32>()
+                + HOG_EPSILON * HOG_EPSILON)
+                .sqrt();
+            descriptor[start..].iter_mut().for_each(|v| *v /= norm);
+        }
+    }
+    descriptor
+}
+
+/// Refine the locations of corners to sub-pixel accuracy, in the manner of OpenCV's `cornerSubPix`. The gradient at
+/// any point of the neighborhood of a corner is orthogonal to the vector joining them, both along its edges and in
+/// uniform areas, so each estimate is iteratively moved to the least-squares solution of this condition over the
+/// `window x window` pixels centered on it, weighted by a Gaussian window.
+///
+/// Returns the refined location of each corner. A corner is left at its initial location if the system is
+/// degenerate, e.g. in uniform areas or along straight edges, or if the estimate leaves the window around it.
+pub fn refine_corners(
+    img: &dyn Image2D<Luma<u8>>,
+    corners: &[(u32, u32)],
+    window: u32,
+) -> Vec<(f32, f32)> {
+    let (gx, gy) = gradients(img);
+    let (w, h) = (i64::from(img.width()), i64::from(img.height()));
+    let radius = i64::from(window / 2);
+    let sigma = (radius as f32).max(1.) / 2.;
+    corners
+        .iter()
+        .map(|&(cx, cy)| {
+            let initial = (cx as f32, cy as f32);
+            let mut estimate = initial;
+            for _ in 0..CORNER_MAX_ITERATIONS {
+                let (ex, ey) = (estimate.0.round() as i64, estimate.1.round() as i64);
+                // Sums of the structure tensor G and of G * p over the window.
+                let (mut gxx, mut gxy, mut gyy, mut bx, mut by) = (0., 0., 0., 0., 0.);
+                for y in (ey - radius).max(0)..=(ey + radius).min(h - 1) {
+                    for x in (ex - radius).max(0)..=(ex + radius).min(w - 1) {
+                        let (dx, dy) = (x as f32 - estimate.0, y as f32 - estimate.1);
+                        let weight = (-(dx * dx + dy * dy) / (2. * sigma * sigma)).exp();
+                        let ix = gx.get_pixel(x as u32, y as u32).data[0];
+                        let iy = gy.get_pixel(x as u32, y as u32).data[0];
+                        let (xx, xy, yy) = (weight * ix * ix, weight * ix * iy, weight * iy * iy);
+                        gxx += xx;
+                        gxy += xy;
+                        gyy += yy;
+                        bx += xx * x as f32 + xy * y as f32;
+                        by += xy * x as f32 + yy * y as f32;
+                    }
+                }
+                let det = gxx * gyy - gxy * gxy;
+                if det <= 1e-6 * (gxx + gyy) * (gxx + gyy) {
+                    return initial;
+                }
+                let next = ((gyy * bx - gxy * by) / det, (gxx * by - gxy * bx) / det);
+                let shift = ((next.0 - estimate.0).powi(2) + (next.1 - estimate.1).powi(2)).sqrt();
+                estimate = next;
+                if shift < CORNER_EPSILON {
+                    break;
+                }
+            }
+            let limit = radius as f32 + 0.5;
+            if (estimate.0 - initial.0).abs() > limit || (estimate.1 - initial.1).abs() > limit {
+                initial
+            } else {
+                estimate
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::ImageBuffer2D;
+
+    // Return the bin accumulating the largest total weight over the whole descriptor.
+    fn dominant_bin(descriptor: &[f32], bins: usize) -> usize {
+        let mut totals = vec![0.; bins];
+        for (i, v) in descriptor.iter().enumerate() {
+            totals[i % bins] += v;
+        }
+        (0..bins)
+            .max_by(|&a, &b| totals[a].partial_cmp(&totals[b]).unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_hog() {
+        let vertical =
+            ImageBuffer2D::generate(34, 32, |(x, _)| Luma::new([if x < 16 { 0u8 } else { 255 }]));
+        let descriptor = hog(&vertical, 8, 8, 2);
+        // 4 x 4 cells, hence 3 x 3 blocks of 2 x 2 cells.
+        assert_eq!(descriptor.len(), 3 * 3 * 4 * 8);
+        assert_eq!(dominant_bin(&descriptor, 8), 0);
+
+        let horizontal =
+            ImageBuffer2D::generate(32, 32, |(_, y)| Luma::new([if y < 16 { 255u8 } else { 0 }]));
+        assert_eq!(dominant_bin(&hog(&horizontal, 8, 8, 2), 8), 4);
+
+        let diagonal = ImageBuffer2D::generate(32, 32, |(x, y)| {
+            Luma::new([if x + y < 32 { 0u8 } else { 255 }])
+        });
+        assert_eq!(dominant_bin(&hog(&diagonal, 8, 8, 2), 8), 2);
+
+        // Blocks are normalized, and uniform blocks remain null.
+        let descriptor = hog(&vertical, 8, 8, 2);
+        for block in descriptor.chunks(4 * 8) {
+            let norm = block.iter().map(|v| v * v).sum::<f32>().sqrt();
+            assert!(norm < 1e-6 || (norm - 1.).abs() < 1e-3, "{}", norm);
+        }
+        let uniform = ImageBuffer2D::generate(16, 16, |_| Luma::new([128u8]));
+        assert!(hog(&uniform, 4, 9, 2).iter().all(|&v| v == 0.));
+        assert!(hog(&uniform, 8, 9, 3).is_empty());
+    }
+
+    #[test]
+    fn test_refine_corners() {
+        // A bright quadrant whose corner lies at a fractional position, antialiased by the area covered in each pixel.
+        let (cx, cy) = (10.4f32, 12.7f32);
+        let img = ImageBuffer2D::generate(24, 24, |(x, y)| {
+            let cover_x = (x as f32 + 0.5 - cx).clamp(0., 1.);
+            let cover_y = (y as f32 + 0.5 - cy).clamp(0., 1.);
+            Luma::new([(40. + 180. * cover_x * cover_y).round() as u8])
+        });
+        let refined = refine_corners(&img, &[(10, 13), (11, 12)], 7);
+        for (&(x, y), &(rx, ry)) in [(10, 13), (11, 12)].iter().zip(&refined) {
+            let before = ((x as f32 - cx).powi(2) + (y as f32 - cy).powi(2)).sqrt();
+            let after = ((rx - cx).powi(2) + (ry - cy).powi(2)).sqrt();
+            assert!(
+                after < before && after < 0.15,
+                "({}, {}): {} -> {}",
+                rx,
+                ry,
+                before,
+                after
+            );
+        }
+
+        // Uniform areas do not define a corner.
+        let uniform = ImageBuffer2D::generate(16, 16, |_| Luma::new([90u8]));
+        assert_eq!(refine_corners(&uniform, &[(8, 8)], 5), vec![(8., 8.)]);
+    }
+}
@@ -0,0 +1,85 @@
+//! Contains operations performed in the frequency domain. Requires the `fft` feature.
+
+use core::{Image2D, ImageBuffer2D, Luma};
+
+use rustfft::num_complex::Complex;
+use rustfft::FftPlanner;
+
+use std::f32::consts::PI;
+
+// Transpose a `w x h` row-major buffer.
+fn transpose(data: &[Complex<f32>], w: usize, h: usize) -> Vec<Complex<f32>> {
+    (0..w * h).map(|i| data[(i % h) * w + i / h]).collect()
+}
+
+// Compute the 2D discrete Fourier transform of a `w x h` row-major buffer in place. The inverse transform is not
+// normalized.
+fn fft2d(data: &mut Vec<Complex<f32>>, w: usize, h: usize, inverse: bool) {
+    let mut planner = FftPlanner::new();
+    let (fft_rows, fft_cols) = if inverse {
+        (planner.plan_fft_inverse(w), planner.plan_fft_inverse(h))
+    } else {
+        (planner.plan_fft_forward(w), planner.plan_fft_forward(h))
+    };
+    fft_rows.process(data);
+    let mut transposed = transpose(data, w, h);
+    fft_cols.process(&mut transposed);
+    *data = transpose(&transposed, h, w);
+}
+
+// Signed frequency of the `k`-th bin of a transform of length `n`.
+fn frequency(k: usize, n: usize) -> f32 {
+    if k <= n / 2 {
+        k as f32 / n as f32
+    } else {
+        (k as f32 - n as f32) / n as f32
+    }
+}
+
+/// Translate an image by a possibly fractional offset, by multiplying its spectrum with a linear phase ramp. The image
+/// is treated as periodic, so content leaving one side reappears on the opposite side. For even dimensions, the Nyquist
+/// frequency is attenuated by fractional shifts to keep the result real.
+pub fn shift_subpixel(img: &dyn Image2D<Luma<f32>>, dx: f32, dy: f32) -> ImageBuffer2D<Luma<f32>> {
+    let (w, h) = img.dimensions();
+    if w == 0 || h == 0 {
+        return img.to_owned();
+    }
+    let (wu, hu) = (w as usize, h as usize);
+    let mut spectrum = img
+        .iter()
+        .map(|p| Complex::new(p.data[0], 0.))
+        .collect::<Vec<Complex<f32>>>();
+    fft2d(&mut spectrum, wu, hu, false);
+    for (i, c) in spectrum.iter_mut().enumerate() {
+        let (kx, ky) = (i % wu, i / wu);
+        let phase = -2. * PI * (frequency(kx, wu) * dx + frequency(ky, hu) * dy);
+        *c *= Complex::from_polar(1., phase);
+    }
+    fft2d(&mut spectrum, wu, hu, true);
+    let norm = (wu * hu) as f32;
+    let shifted = spectrum.iter().map(|c| Luma::new([c.re / norm])).collect();
+    ImageBuffer2D::from_vec(w, h, shifted).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shift_subpixel() {
+        let img =
+            ImageBuffer2D::generate(8, 6, |(x, y)| Luma::new([((x * 7 + y * 3) % 11) as f32]));
+        let shifted = shift_subpixel(&img, 3., -2.);
+        for ((y, x), p) in shifted.enumerate_pixels() {
+            let (sx, sy) = ((x + 8 - 3) % 8, (y + 2) % 6);
+            assert!((p.data[0] - img.get_pixel(sx as u32, sy as u32).data[0]).abs() < 1e-3);
+        }
+
+        let img =
+            ImageBuffer2D::generate(9, 7, |(x, y)| Luma::new([((x * 5 + y * 2) % 13) as f32]));
+        let round_trip = shift_subpixel(&shift_subpixel(&img, 0.3, 1.7), -0.3, -1.7);
+        for (p, q) in round_trip.iter().zip(img.iter()) {
+            assert!((p.data[0] - q.data[0]).abs() < 1e-3);
+        }
+    }
+}
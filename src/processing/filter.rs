@@ -0,0 +1,837 @@
+//! Contains linear filters and image measures derived from them.
+
+use core::{Image2D, Image2DMut, Image2DView, ImageBuffer2D, Luma, Pixel, Rect};
+use helper::generic::round_saturate;
+use processing::map::map_pixels;
+
+use failure::Error;
+use num_traits::cast::cast;
+
+use std::cmp::{max, min};
+
+// Apply a separable filter: `kernel_x` horizontally then `kernel_y` vertically. Both kernels must have an odd length and
+// are centered on the current pixel. Pixels falling out of the image are replaced by the closest border pixel.
+fn separable_filter<P>(img: &dyn Image2D<P>, kernel_x: &[f32], kernel_y: &[f32]) -> ImageBuffer2D<P>
+where
+    P: Pixel,
+{
+    let (w, h) = img.dimensions();
+    let (rx, ry) = ((kernel_x.len() / 2) as i64, (kernel_y.len() / 2) as i64);
+    let n_channels = P::N_CHANNELS as usize;
+
+    // Horizontal pass, storing the intermediate result as floating point values.
+    let mut tmp = vec![0f32; w as usize * h as usize * n_channels];
+    for (i, pix) in tmp.chunks_mut(n_channels).enumerate() {
+        let (x, y) = ((i % w as usize) as i64, (i / w as usize) as u32);
+        for (j, k) in kernel_x.iter().enumerate() {
+            let sx = min(max(x + j as i64 - rx, 0), i64::from(w) - 1) as u32;
+            for (a, c) in pix.iter_mut().zip(img.get_pixel(sx, y).channels()) {
+                *a += k * cast::<P::Subpixel, f32>(*c).unwrap();
+            }
+        }
+    }
+
+    // Vertical pass.
+    let mut accu = vec![0f32; n_channels];
+    let mut channels = Vec::with_capacity(n_channels);
+    ImageBuffer2D::generate(w, h, |(x, y)| {
+        accu.iter_mut().for_each(|a| *a = 0.);
+        for (i, k) in kernel_y.iter().enumerate() {
+            let sy = min(max(i64::from(y) + i as i64 - ry, 0), i64::from(h) - 1) as usize;
+            let offset = (sy * w as usize + x as usize) * n_channels;
+            for (a, c) in accu.iter_mut().zip(&tmp[offset..offset + n_channels]) {
+                *a += k * c;
+            }
+        }
+        channels.clear();
+        channels.extend(accu.iter().map(|a| round_saturate::<P::Subpixel>(*a)));
+        P::from_slice(&channels)
+    })
+}
+
+/// Blur an image with a gaussian filter of standard deviation `sigma`. The kernel is truncated at 3 standard deviations
+/// and pixels falling out of the image are replaced by the closest border pixel. A non-positive `sigma` returns a copy
+/// of the image.
+pub fn gaussian_blur<P>(img: &dyn Image2D<P>, sigma: f32) -> ImageBuffer2D<P>
+where
+    P: Pixel,
+{
+    if sigma <= 0. {
+        return img.to_owned();
+    }
+    let radius = (3. * sigma).ceil() as i64;
+    let mut kernel = (-radius..=radius)
+        .map(|i| (-((i * i) as f32) / (2. * sigma * sigma)).exp())
+        .collect::<Vec<f32>>();
+    let sum = kernel.iter().sum::<f32>();
+    kernel.iter_mut().for_each(|k| *k /= sum);
+    separable_filter(img, &kernel, &kernel)
+}
+
+/// Compute the horizontal and vertical derivatives of a grayscale image with the Sobel operator, normalized so that a
+/// linear ramp of slope 1 has a derivative of 1. Pixels falling out of the image are replaced by the closest border
+/// pixel.
+pub fn gradients(
+    img: &dyn Image2D<Luma<u8>>,
+) -> (ImageBuffer2D<Luma<f32>>, ImageBuffer2D<Luma<f32>>) {
+    let img = map_pixels(img, |p| Luma::new([f32::from(p.data[0])]));
+    let (derivative, smoothing) = ([-0.5, 0., 0.5], [0.25, 0.5, 0.25]);
+    (
+        separable_filter(&img, &derivative, &smoothing),
+        separable_filter(&img, &smoothing, &derivative),
+    )
+}
+
+// Pair of single channel floating point images storing the two components of a vector field.
+type ComponentImages = (ImageBuffer2D<Luma<f32>>, ImageBuffer2D<Luma<f32>>);
+
+/// Convert a vector field stored as two component images, such as the output of `gradients` or an optical flow, into
+/// its magnitude and angle images. Angles are in radians, in `[-pi, pi]`, measured from the x axis towards the y axis.
+///
+/// **Error**: if the dimensions of the component images do not match.
+pub fn cartesian_to_polar(
+    x: &dyn Image2D<Luma<f32>>,
+    y: &dyn Image2D<Luma<f32>>,
+) -> Result<ComponentImages, Error> {
+    if x.dimensions() != y.dimensions() {
+        bail!("Image dimensions do not match");
+    }
+    let (w, h) = x.dimensions();
+    let (magnitude, angle) = x
+        .iter()
+        .zip(y.iter())
+        .map(|(px, py)| {
+            let (vx, vy) = (px.data[0], py.data[0]);
+            (Luma::new([vx.hypot(vy)]), Luma::new([vy.atan2(vx)]))
+        })
+        .unzip();
+    Ok((
+        ImageBuffer2D::from_vec(w, h, magnitude)?,
+        ImageBuffer2D::from_vec(w, h, angle)?,
+    ))
+}
+
+/// Convert a vector field stored as magnitude and angle images, in radians, into its x and y component images. This is
+/// the inverse of `cartesian_to_polar`.
+///
+/// **Error**: if the dimensions of the magnitude and angle images do not match.
+pub fn polar_to_cartesian(
+    magnitude: &dyn Image2D<Luma<f32>>,
+    angle: &dyn Image2D<Luma<f32>>,
+) -> Result<ComponentImages, Error> {
+    if magnitude.dimensions() != angle.dimensions() {
+        bail!("Image dimensions do not match");
+    }
+    let (w, h) = magnitude.dimensions();
+    let (x, y) = magnitude
+        .iter()
+        .zip(angle.iter())
+        .map(|(pm, pa)| {
+            let (sin, cos) = pa.data[0].sin_cos();
+            (Luma::new([pm.data[0] * cos]), Luma::new([pm.data[0] * sin]))
+        })
+        .unzip();
+    Ok((
+        ImageBuffer2D::from_vec(w, h, x)?,
+        ImageBuffer2D::from_vec(w, h, y)?,
+    ))
+}
+
+/// Compute the Laplacian of a grayscale image, using the 4-connected discrete Laplacian operator. Pixels falling out of
+/// the image are replaced by the closest border pixel.
+pub fn laplacian(img: &dyn Image2D<Luma<u8>>) -> ImageBuffer2D<Luma<i16>> {
+    let (w, h) = img.dimensions();
+    let value = |x: i64, y: i64| {
+        let x = min(max(x, 0), i64::from(w) - 1) as u32;
+        let y = min(max(y, 0), i64::from(h) - 1) as u32;
+        i16::from(img.get_pixel(x, y).data[0])
+    };
+    ImageBuffer2D::generate(w, h, |(x, y)| {
+        let (x, y) = (i64::from(x), i64::from(y));
+        Luma::new([
+            value(x - 1, y) + value(x + 1, y) + value(x, y - 1) + value(x, y + 1) - 4 * value(x, y),
+        ])
+    })
+}
+
+/// Compute the variance of the Laplacian of a grayscale image. This is a standard sharpness measure: blurry images have
+/// a low variance. Returns 0 for an empty image.
+pub fn variance_of_laplacian(img: &dyn Image2D<Luma<u8>>) -> f64 {
+    let lap = laplacian(img);
+    let n = f64::from(img.width()) * f64::from(img.height());
+    if n == 0. {
+        return 0.;
+    }
+    let mean = lap.iter().map(|p| f64::from(p.data[0])).sum::<f64>() / n;
+    lap.iter()
+        .map(|p| {
+            let d = f64::from(p.data[0]) - mean;
+            d * d
+        })
+        .sum::<f64>()
+        / n
+}
+
+// Compute the mean of the `(2 * radius + 1) x (2 * radius + 1)` window centered on each element of a `w x h` buffer,
+// using an integral image. Windows are cropped to the buffer, and the mean is taken over the remaining elements.
+fn box_mean(data: &[f32], w: usize, h: usize, radius: usize) -> Vec<f32> {
+    let mut integral = vec![0f64; (w + 1) * (h + 1)];
+    for y in 0..h {
+        let mut row_sum = 0.;
+        for x in 0..w {
+            row_sum += f64::from(data[y * w + x]);
+            integral[(y + 1) * (w + 1) + x + 1] = integral[y * (w + 1) + x + 1] + row_sum;
+        }
+    }
+    let mut out = Vec::with_capacity(w * h);
+    for y in 0..h {
+        let (y0, y1) = (y.saturating_sub(radius), min(y + radius + 1, h));
+        for x in 0..w {
+            let (x0, x1) = (x.saturating_sub(radius), min(x + radius + 1, w));
+            let sum = integral[y1 * (w + 1) + x1]
+                - integral[y0 * (w + 1) + x1]
+                - integral[y1 * (w + 1) + x0]
+                + integral[y0 * (w + 1) + x0];
+            out.push((sum / ((x1 - x0) * (y1 - y0)) as f64) as f32);
+        }
+    }
+    out
+}
+
+/// Apply the edge-preserving guided filter of He et al. to `input`, using the structure of `guide`. The output is
+/// locally a linear transform of the guide, fitted over `(2 * radius + 1) x (2 * radius + 1)` windows; `eps`
+/// regularizes the fit, larger values giving a smoother result. Windows are cropped at the image borders.
+///
+/// **Error**: if the dimensions of `input` and `guide` do not match.
+pub fn guided_filter(
+    input: &dyn Image2D<Luma<f32>>,
+    guide: &dyn Image2D<Luma<f32>>,
+    radius: u32,
+    eps: f32,
+) -> Result<ImageBuffer2D<Luma<f32>>, Error> {
+    if input.dimensions() != guide.dimensions() {
+        bail!("Image dimensions do not match");
+    }
+    let (w, h) = input.dimensions();
+    let (wu, hu, r) = (w as usize, h as usize, radius as usize);
+    let p = input.iter().map(|p| p.data[0]).collect::<Vec<f32>>();
+    let i = guide.iter().map(|p| p.data[0]).collect::<Vec<f32>>();
+    let product = |a: &[f32], b: &[f32]| a.iter().zip(b).map(|(a, b)| a * b).collect::<Vec<f32>>();
+
+    let mean_i = box_mean(&i, wu, hu, r);
+    let mean_p = box_mean(&p, wu, hu, r);
+    let corr_ii = box_mean(&product(&i, &i), wu, hu, r);
+    let corr_ip = box_mean(&product(&i, &p), wu, hu, r);
+
+    let mut a = Vec::with_capacity(p.len());
+    let mut b = Vec::with_capacity(p.len());
+    for k in 0..p.len() {
+        let var_i = corr_ii[k] - mean_i[k] * mean_i[k];
+        let cov_ip = corr_ip[k] - mean_i[k] * mean_p[k];
+        let ak = cov_ip / (var_i + eps);
+        a.push(ak);
+        b.push(mean_p[k] - ak * mean_i[k]);
+    }
+    let mean_a = box_mean(&a, wu, hu, r);
+    let mean_b = box_mean(&b, wu, hu, r);
+    let out = (0..p.len())
+        .map(|k| Luma::new([mean_a[k] * i[k] + mean_b[k]]))
+        .collect();
+    ImageBuffer2D::from_vec(w, h, out)
+}
+
+/// Upsample `low` to `out_w x out_h` with a joint bilateral filter: each output pixel is a weighted average of the
+/// nearby pixels of `low`, weighted both by their distance and by the similarity of `guide` at the output pixel and at
+/// their location. Edges of the high resolution guide are thus preserved, which is typically used to upsample depth
+/// maps computed at a low resolution. The guide usually has the output dimensions, and is otherwise sampled at the
+/// corresponding relative positions.
+///
+/// **Panics** if `low` or `guide` is empty while the output dimensions are not.
+pub fn joint_bilateral_upsample(
+    low: &dyn Image2D<Luma<f32>>,
+    guide: &dyn Image2D<Luma<u8>>,
+    out_w: u32,
+    out_h: u32,
+) -> ImageBuffer2D<Luma<f32>> {
+    // Standard deviations of the spatial weights, in low resolution pixels, and of the range weights, in guide levels.
+    const SIGMA_SPATIAL: f32 = 1.;
+    const SIGMA_RANGE: f32 = 12.;
+    const RADIUS: i64 = 2;
+
+    let (lw, lh) = low.dimensions();
+    let (gw, gh) = guide.dimensions();
+    // Guide value at a position given in output pixels.
+    let guide_at = |x: f32, y: f32| {
+        let gx = min(
+            max(((x + 0.5) * gw as f32 / out_w as f32) as i64, 0),
+            i64::from(gw) - 1,
+        );
+        let gy = min(
+            max(((y + 0.5) * gh as f32 / out_h as f32) as i64, 0),
+            i64::from(gh) - 1,
+        );
+        f32::from(guide.get_pixel(gx as u32, gy as u32).data[0])
+    };
+    let (scale_x, scale_y) = (lw as f32 / out_w as f32, lh as f32 / out_h as f32);
+    ImageBuffer2D::generate(out_w, out_h, |(x, y)| {
+        let reference = guide_at(x as f32, y as f32);
+        // Position of the output pixel in the low resolution image.
+        let lx = (x as f32 + 0.5) * scale_x - 0.5;
+        let ly = (y as f32 + 0.5) * scale_y - 0.5;
+        let (cx, cy) = (lx.round() as i64, ly.round() as i64);
+        let (mut sum, mut total) = (0., 0.);
+        for sy in max(cy - RADIUS, 0)..=min(cy + RADIUS, i64::from(lh) - 1) {
+            for sx in max(cx - RADIUS, 0)..=min(cx + RADIUS, i64::from(lw) - 1) {
+                let (dx, dy) = (sx as f32 - lx, sy as f32 - ly);
+                let g = guide_at(
+                    (sx as f32 + 0.5) / scale_x - 0.5,
+                    (sy as f32 + 0.5) / scale_y - 0.5,
+                );
+                let weight = (-(dx * dx + dy * dy) / (2. * SIGMA_SPATIAL * SIGMA_SPATIAL)
+                    - (g - reference) * (g - reference) / (2. * SIGMA_RANGE * SIGMA_RANGE))
+                    .exp();
+                sum += weight * low.get_pixel(sx as u32, sy as u32).data[0];
+                total += weight;
+            }
+        }
+        if total > 0. {
+            Luma::new([sum / total])
+        } else {
+            // All the neighbors lie across an edge, fall back to the closest one.
+            let sx = min(max(cx, 0), i64::from(lw) - 1) as u32;
+            let sy = min(max(cy, 0), i64::from(lh) - 1) as u32;
+            *low.get_pixel(sx, sy)
+        }
+    })
+}
+
+/// Denoise a grayscale image with the non-local means algorithm: each pixel is replaced by a weighted average of the
+/// pixels of a `search x search` window centered on it, each pixel being weighted by the similarity of the
+/// `template x template` patches centered on both pixels. The weight of a pixel whose patch has a mean squared
+/// difference `d` with the patch of the center pixel is `exp(-d / h^2)`, so `h` sets the filtering strength and is
+/// typically one to two times the standard deviation of the noise. Both window sizes should be odd, and pixels falling
+/// out of the image are replaced by the closest border pixel.
+///
+/// Patch distances are accumulated with integral images, so the complexity is `O(width * height * search^2)`
+/// regardless of `template`. The cost still grows quickly with `search`: small sizes such as a template of 5 and a
+/// search window of 11 are usually sufficient.
+pub fn nl_means(
+    img: &dyn Image2D<Luma<u8>>,
+    h: f32,
+    template: u32,
+    search: u32,
+) -> ImageBuffer2D<Luma<u8>> {
+    let (w, ht) = img.dimensions();
+    let (wu, hu) = (w as usize, ht as usize);
+    let values = img
+        .iter()
+        .map(|p| f32::from(p.data[0]))
+        .collect::<Vec<f32>>();
+    let radius = i64::from(search / 2);
+    let mut sums = vec![0f32; values.len()];
+    let mut weights = vec![0f32; values.len()];
+    let mut shifted = vec![0f32; values.len()];
+    let mut diff2 = vec![0f32; values.len()];
+    for dy in -radius..=radius {
+        for dx in -radius..=radius {
+            for y in 0..hu {
+                let sy = min(max(y as i64 + dy, 0), hu as i64 - 1) as usize;
+                for x in 0..wu {
+                    let sx = min(max(x as i64 + dx, 0), wu as i64 - 1) as usize;
+                    let (k, v) = (y * wu + x, values[sy * wu + sx]);
+                    shifted[k] = v;
+                    diff2[k] = (values[k] - v) * (values[k] - v);
+                }
+            }
+            let distances = box_mean(&diff2, wu, hu, (template / 2) as usize);
+            for k in 0..values.len() {
+                let weight = (-distances[k].max(0.) / (h * h)).exp();
+                sums[k] += weight * shifted[k];
+                weights[k] += weight;
+            }
+        }
+    }
+    let out = sums
+        .iter()
+        .zip(&weights)
+        .map(|(s, w)| Luma::new([round_saturate(s / w)]))
+        .collect();
+    ImageBuffer2D::from_vec(w, ht, out).unwrap()
+}
+
+/// Smooth a grayscale image while preserving its edges with the anisotropic diffusion of Perona and Malik. At each of
+/// the `iterations` steps, each pixel receives from its 4 neighbors a flux proportional to their difference `d`,
+/// weighted by the conduction function `exp(-(d / kappa)^2)`: differences much larger than `kappa` are considered
+/// edges and barely diffuse. `lambda` is the integration step, which must not exceed 0.25 for the scheme to be stable.
+/// The image borders are insulating.
+///
+/// **Panics** if `lambda` is not in `[0, 0.25]`.
+pub fn anisotropic_diffusion(
+    img: &dyn Image2D<Luma<u8>>,
+    iterations: u32,
+    kappa: f32,
+    lambda: f32,
+) -> ImageBuffer2D<Luma<u8>> {
+    assert!(
+        (0. ..=0.25).contains(&lambda),
+        "The integration step must be in [0, 0.25] for the diffusion to be stable."
+    );
+    let (w, h) = (img.width() as usize, img.height() as usize);
+    let mut values = img
+        .iter()
+        .map(|p| f32::from(p.data[0]))
+        .collect::<Vec<f32>>();
+    let mut next = values.clone();
+    let conduction = |d: f32| (-(d / kappa) * (d / kappa)).exp();
+    for _ in 0..iterations {
+        for y in 0..h {
+            for x in 0..w {
+                let k = y * w + x;
+                let v = values[k];
+                let mut flux = 0.;
+                let mut neighbors = [None; 4];
+                if x > 0 {
+                    neighbors[0] = Some(k - 1);
+                }
+                if x + 1 < w {
+                    neighbors[1] = Some(k + 1);
+                }
+                if y > 0 {
+                    neighbors[2] = Some(k - w);
+                }
+                if y + 1 < h {
+                    neighbors[3] = Some(k + w);
+                }
+                for n in neighbors.iter().filter_map(|&n| n) {
+                    let d = values[n] - v;
+                    flux += conduction(d) * d;
+                }
+                next[k] = v + lambda * flux;
+            }
+        }
+        ::std::mem::swap(&mut values, &mut next);
+    }
+    let out = values
+        .iter()
+        .map(|v| Luma::new([round_saturate(*v)]))
+        .collect();
+    ImageBuffer2D::from_vec(img.width(), img.height(), out).unwrap()
+}
+
+/// Compute the sum of every `w x h` window fully contained in the image, indexed by the window's top-left corner. The
+/// output has dimensions `(width - w + 1, height - h + 1)`, and is empty if the window is larger than the image. An
+/// integral image is used internally, so the cost per window does not depend on its size. The integral image is
+/// computed with wrapping arithmetic, which keeps every window sum exact as long as it fits in a `u32`, i.e. for windows
+/// of up to 16843009 pixels, whatever the size of the image.
+///
+/// **Panics** if `w` or `h` is zero.
+pub fn windowed_sum(img: &dyn Image2D<Luma<u8>>, w: u32, h: u32) -> ImageBuffer2D<Luma<u32>> {
+    assert!(w > 0 && h > 0, "Window dimensions must be non-zero");
+    let (img_w, img_h) = (img.width() as usize, img.height() as usize);
+    let mut integral = vec![0u32; (img_w + 1) * (img_h + 1)];
+    for (y, row) in img.rows().enumerate() {
+        let mut row_sum = 0u32;
+        for (x, p) in row.iter().enumerate() {
+            row_sum = row_sum.wrapping_add(u32::from(p.data[0]));
+            integral[(y + 1) * (img_w + 1) + x + 1] =
+                integral[y * (img_w + 1) + x + 1].wrapping_add(row_sum);
+        }
+    }
+    let out_w = (img_w + 1).saturating_sub(w as usize);
+    let out_h = (img_h + 1).saturating_sub(h as usize);
+    let at = |x: usize, y: usize| integral[y * (img_w + 1) + x];
+    ImageBuffer2D::generate(out_w as u32, out_h as u32, |(x, y)| {
+        let (x0, y0) = (x as usize, y as usize);
+        let (x1, y1) = (x0 + w as usize, y0 + h as usize);
+        Luma::new([at(x1, y1)
+            .wrapping_add(at(x0, y0))
+            .wrapping_sub(at(x1, y0))
+            .wrapping_sub(at(x0, y1))])
+    })
+}
+
+/// Reduce every `w x h` window fully contained in the image to a single pixel with `f`, which receives a view of the
+/// window. The output is indexed by the window's top-left corner, has dimensions `(width - w + 1, height - h + 1)` and
+/// is empty if the window is larger than the image. This is a generic building block for custom local filters, whose
+/// cost is proportional to the number of windows times the cost of `f`.
+///
+/// **Panics** if `w` or `h` is zero.
+pub fn window_reduce<P, F>(img: &dyn Image2D<P>, w: u32, h: u32, f: F) -> ImageBuffer2D<P>
+where
+    P: Pixel,
+    F: Fn(Image2DView<P>) -> P,
+{
+    assert!(w > 0 && h > 0, "Window dimensions must be non-zero");
+    let out_w = (img.width() + 1).saturating_sub(w);
+    let out_h = (img.height() + 1).saturating_sub(h);
+    ImageBuffer2D::generate(out_w, out_h, |(x, y)| {
+        f(img.sub_image(Rect::new(x, y, w, h)))
+    })
+}
+
+/// Emboss a grayscale image: the image is convolved with a 3x3 kernel differentiating along `direction`, an angle in
+/// radians measured from the x axis towards the y axis, and a bias of 128 is added so that flat regions become mid-gray.
+/// Pixels falling out of the image are replaced by the closest border pixel.
+pub fn emboss(img: &dyn Image2D<Luma<u8>>, direction: f32) -> ImageBuffer2D<Luma<u8>> {
+    let (dir_x, dir_y) = (direction.cos(), direction.sin());
+    let (w, h) = img.dimensions();
+    let value = |x: i64, y: i64| {
+        let x = min(max(x, 0), i64::from(w) - 1) as u32;
+        let y = min(max(y, 0), i64::from(h) - 1) as u32;
+        f32::from(img.get_pixel(x, y).data[0])
+    };
+    ImageBuffer2D::generate(w, h, |(x, y)| {
+        let (x, y) = (i64::from(x), i64::from(y));
+        let mut relief = 0.;
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                relief += (dx as f32 * dir_x + dy as f32 * dir_y) * value(x + dx, y + dy);
+            }
+        }
+        Luma::new([round_saturate(relief + 128.)])
+    })
+}
+
+/// Pixelate an image: each `block x block` tile is replaced by its average color. The output has the same dimensions as
+/// the input, and partial tiles at the right and bottom edges average over their valid region.
+///
+/// **Panics** if `block` is zero.
+pub fn pixelate<P>(img: &dyn Image2D<P>, block: u32) -> ImageBuffer2D<P>
+where
+    P: Pixel,
+{
+    let mut out = img.to_owned();
+    let n_channels = P::N_CHANNELS as usize;
+    let mut accu = vec![0f32; n_channels];
+    for (rect, tile) in img.tiles(block, block) {
+        accu.iter_mut().for_each(|a| *a = 0.);
+        for p in tile.iter() {
+            for (a, c) in accu.iter_mut().zip(p.channels()) {
+                *a += cast::<P::Subpixel, f32>(*c).unwrap();
+            }
+        }
+        let n = (rect.width() * rect.height()) as f32;
+        let channels = accu
+            .iter()
+            .map(|a| round_saturate(a / n))
+            .collect::<Vec<P::Subpixel>>();
+        out.fill_rect(rect, &P::from_slice(&channels));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::Rect;
+
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+
+    #[test]
+    fn test_gaussian_blur() {
+        let img = ImageBuffer2D::generate(9, 7, |_| Luma::new([100u8]));
+        assert_eq!(gaussian_blur(&img, 1.5), img);
+
+        let mut img = ImageBuffer2D::new(9, 9);
+        img.put_pixel(4, 4, Luma::new([1000f32]));
+        let blurred = gaussian_blur(&img, 1.);
+        let total = blurred.iter().map(|p| p.data[0]).sum::<f32>();
+        assert!((total - 1000.).abs() < 1e-2);
+        assert_eq!(blurred.get_pixel(3, 4), blurred.get_pixel(5, 4));
+        assert_eq!(blurred.get_pixel(4, 3), blurred.get_pixel(4, 5));
+        assert!(blurred.get_pixel(4, 4).data[0] > blurred.get_pixel(3, 4).data[0]);
+    }
+
+    #[test]
+    fn test_gaussian_blur_view() {
+        let img = ImageBuffer2D::generate(16, 12, |(x, y)| {
+            Luma::new([((x * 31 + y * 17) % 256) as u8])
+        });
+        let rect = Rect::new(3, 2, 9, 7);
+        let view = img.sub_image(rect);
+        let blurred = gaussian_blur(&view as &dyn Image2D<Luma<u8>>, 1.2);
+        assert_eq!(blurred.dimensions(), (9, 7));
+        assert_eq!(blurred, gaussian_blur(&img.sub_image(rect).to_owned(), 1.2));
+    }
+
+    #[test]
+    fn test_gradients() {
+        let img = ImageBuffer2D::generate(10, 8, |(x, y)| Luma::new([(3 * x + 5 * y) as u8]));
+        let (gx, gy) = gradients(&img);
+        for y in 1..7 {
+            for x in 1..9 {
+                assert_eq!(gx.get_pixel(x, y), &Luma::new([3.]));
+                assert_eq!(gy.get_pixel(x, y), &Luma::new([5.]));
+            }
+        }
+        // Borders are clamped, halving the derivative across them.
+        assert_eq!(gx.get_pixel(0, 4), &Luma::new([1.5]));
+        assert_eq!(gy.get_pixel(4, 7), &Luma::new([2.5]));
+    }
+
+    #[test]
+    fn test_cartesian_polar() {
+        let x = ImageBuffer2D::generate(9, 7, |(x, y)| Luma::new([x as f32 - 4. + 0.1 * y as f32]));
+        let y = ImageBuffer2D::generate(9, 7, |(x, y)| Luma::new([(x * y) as f32 * 0.5 - 3.]));
+        let (magnitude, angle) = cartesian_to_polar(&x, &y).unwrap();
+        assert!((magnitude.get_pixel(0, 0).data[0] - 5.).abs() < 1e-6);
+        assert!((angle.get_pixel(4, 6).data[0] - 9f32.atan2(0.6)).abs() < 1e-6);
+        assert!(angle.get_pixel(0, 0).data[0] < -FRAC_PI_2);
+
+        let (x2, y2) = polar_to_cartesian(&magnitude, &angle).unwrap();
+        for ((a, b), (c, d)) in x.iter().zip(x2.iter()).zip(y.iter().zip(y2.iter())) {
+            assert!((a.data[0] - b.data[0]).abs() < 1e-5, "{:?} {:?}", a, b);
+            assert!((c.data[0] - d.data[0]).abs() < 1e-5, "{:?} {:?}", c, d);
+        }
+
+        let small = ImageBuffer2D::<Luma<f32>>::new(9, 6);
+        assert!(cartesian_to_polar(&x, &small).is_err());
+        assert!(polar_to_cartesian(&small, &y).is_err());
+    }
+
+    #[test]
+    fn test_laplacian() {
+        let img =
+            ImageBuffer2D::generate(5, 5, |(x, _y)| Luma::new([if x < 2 { 0u8 } else { 200 }]));
+        let lap = laplacian(&img);
+        assert_eq!(lap.get_pixel(0, 2), &Luma::new([0]));
+        assert_eq!(lap.get_pixel(1, 2), &Luma::new([200]));
+        assert_eq!(lap.get_pixel(2, 2), &Luma::new([-200]));
+        assert_eq!(lap.get_pixel(4, 2), &Luma::new([0]));
+    }
+
+    #[test]
+    fn test_variance_of_laplacian() {
+        let img = ImageBuffer2D::generate(32, 32, |(x, y)| {
+            Luma::new([if (x / 8 + y / 8) % 2 == 0 { 20u8 } else { 230 }])
+        });
+        let blurred = gaussian_blur(&img, 2.);
+        assert!(variance_of_laplacian(&img) > variance_of_laplacian(&blurred));
+        let flat = ImageBuffer2D::generate(8, 8, |_| Luma::new([42u8]));
+        assert_eq!(variance_of_laplacian(&flat), 0.);
+    }
+
+    #[test]
+    fn test_guided_filter() {
+        let noise = |x: u32, y: u32| ((x * 7 + y * 13) % 5) as f32 * 0.01 - 0.02;
+        let img = ImageBuffer2D::generate(20, 12, |(x, y)| {
+            Luma::new([if x < 10 { 0.2 } else { 0.8 } + noise(x, y)])
+        });
+        let filtered = guided_filter(&img, &img, 2, 0.01).unwrap();
+
+        let flat_variance = |img: &ImageBuffer2D<Luma<f32>>| {
+            let values = img
+                .rect_iter(Rect::new(2, 2, 5, 8))
+                .map(|p| p.data[0])
+                .collect::<Vec<f32>>();
+            let mean = values.iter().sum::<f32>() / values.len() as f32;
+            values.iter().map(|v| (v - mean) * (v - mean)).sum::<f32>() / values.len() as f32
+        };
+        assert!(flat_variance(&filtered) * 4. < flat_variance(&img));
+        for y in 0..12 {
+            let step = filtered.get_pixel(11, y).data[0] - filtered.get_pixel(8, y).data[0];
+            assert!(step > 0.5, "{}", step);
+        }
+
+        let small = ImageBuffer2D::<Luma<f32>>::new(5, 5);
+        assert!(guided_filter(&img, &small, 2, 0.01).is_err());
+    }
+
+    #[test]
+    fn test_joint_bilateral_upsample() {
+        // The edge of the low resolution image is at x = 16 once upsampled, but the guide shows it at x = 18.
+        let guide =
+            ImageBuffer2D::generate(32, 24, |(x, _y)| Luma::new([if x < 18 { 20 } else { 230 }]));
+        let low = ImageBuffer2D::generate(8, 6, |(x, _y)| Luma::new([if x < 4 { 1. } else { 5. }]));
+        let up = joint_bilateral_upsample(&low, &guide, 32, 24);
+        assert_eq!(up.dimensions(), (32, 24));
+        for y in 0..24 {
+            assert!((up.get_pixel(12, y).data[0] - 1.).abs() < 0.01);
+            assert!(up.get_pixel(17, y).data[0] < 1.1);
+            assert!(up.get_pixel(18, y).data[0] > 4.9);
+            assert!((up.get_pixel(26, y).data[0] - 5.).abs() < 0.01);
+        }
+
+        // Without edges in the guide, the result is a smooth interpolation.
+        let flat = ImageBuffer2D::<Luma<u8>>::new(32, 24);
+        let smooth = joint_bilateral_upsample(&low, &flat, 32, 24);
+        let v = smooth.get_pixel(16, 5).data[0];
+        assert!(v > 1.5 && v < 4.5, "{}", v);
+    }
+
+    #[cfg(feature = "rand_integration")]
+    #[test]
+    fn test_windowed_sum() {
+        let img = ImageBuffer2D::<Luma<u8>>::rand_seeded(9, 7, 42);
+        for &(w, h) in &[(1, 1), (3, 2), (9, 7), (4, 7)] {
+            let sums = windowed_sum(&img, w, h);
+            assert_eq!(sums.dimensions(), (9 - w + 1, 7 - h + 1));
+            for ((y, x), s) in sums.enumerate_pixels() {
+                let rect = Rect::new(x as u32, y as u32, w, h);
+                let expected = img
+                    .rect_iter(rect)
+                    .map(|p| u32::from(p.data[0]))
+                    .sum::<u32>();
+                assert_eq!(s.data[0], expected);
+            }
+        }
+        assert_eq!(windowed_sum(&img, 10, 2).dimensions(), (0, 6));
+    }
+
+    #[test]
+    fn test_windowed_sum_overflow() {
+        // The sum of the whole image does not fit in a u32.
+        let (w, h) = (4105, 4105);
+        let img = ImageBuffer2D::generate(w, h, |_| Luma::new([255u8]));
+        let sums = windowed_sum(&img, 3, 2);
+        assert_eq!(sums.get_pixel(0, 0), &Luma::new([6 * 255]));
+        assert_eq!(sums.get_pixel(w - 3, h - 2), &Luma::new([6 * 255]));
+        let sums = windowed_sum(&img, 4000, 4000);
+        assert_eq!(sums.get_pixel(105, 105), &Luma::new([4000 * 4000 * 255]));
+    }
+
+    #[cfg(feature = "rand_integration")]
+    #[test]
+    fn test_anisotropic_diffusion() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut img = ImageBuffer2D::generate(40, 30, |(x, _)| {
+            Luma::new([if x < 20 { 60u8 } else { 180 }])
+        });
+        img.add_gaussian_noise(0., 8., &mut StdRng::seed_from_u64(11));
+        // Standard deviation of the interior of the left region, and contrast across the edge.
+        let stats = |img: &ImageBuffer2D<Luma<u8>>| {
+            let region = img
+                .rect_iter(Rect::new(2, 2, 15, 26))
+                .map(|p| f32::from(p.data[0]))
+                .collect::<Vec<f32>>();
+            let mean = region.iter().sum::<f32>() / region.len() as f32;
+            let var =
+                region.iter().map(|v| (v - mean) * (v - mean)).sum::<f32>() / region.len() as f32;
+            let contrast = (0..30)
+                .map(|y| {
+                    f32::from(img.get_pixel(20, y).data[0])
+                        - f32::from(img.get_pixel(19, y).data[0])
+                })
+                .sum::<f32>()
+                / 30.;
+            (var.sqrt(), contrast)
+        };
+        let (noise0, contrast0) = stats(&img);
+        let (noise5, contrast5) = stats(&anisotropic_diffusion(&img, 5, 20., 0.2));
+        let (noise20, contrast20) = stats(&anisotropic_diffusion(&img, 20, 20., 0.2));
+        assert!(noise5 < 0.7 * noise0, "{} vs {}", noise5, noise0);
+        assert!(noise20 < 0.7 * noise5, "{} vs {}", noise20, noise5);
+        assert!(
+            contrast5 > 0.9 * contrast0 && contrast20 > 0.9 * contrast0,
+            "{} {} {}",
+            contrast0,
+            contrast5,
+            contrast20
+        );
+
+        assert_eq!(anisotropic_diffusion(&img, 0, 20., 0.2), img);
+    }
+
+    #[test]
+    fn test_window_reduce() {
+        let img = ImageBuffer2D::generate(11, 8, |(x, y)| {
+            Luma::new([((x * 73 + y * 41 + x * y * 17) % 256) as u8])
+        });
+        let max = |view: Image2DView<Luma<u8>>| *view.iter().max_by_key(|p| p.data[0]).unwrap();
+        let reduced = window_reduce(&img, 3, 2, max);
+        assert_eq!(reduced.dimensions(), (9, 7));
+        for ((y, x), p) in reduced.enumerate_pixels() {
+            let mut expected = 0;
+            for dy in 0..2 {
+                for dx in 0..3 {
+                    expected = expected.max(img.get_pixel(x as u32 + dx, y as u32 + dy).data[0]);
+                }
+            }
+            assert_eq!(p.data[0], expected, "pixel ({}, {})", x, y);
+        }
+
+        // A 1x1 window with the identity reduction copies the image.
+        assert_eq!(window_reduce(&img, 1, 1, |v| *v.get_pixel(0, 0)), img);
+        assert_eq!(window_reduce(&img, 12, 2, max).dimensions(), (0, 7));
+    }
+
+    #[test]
+    fn test_emboss() {
+        let mut img = ImageBuffer2D::generate(12, 12, |_| Luma::new([60u8]));
+        img.fill_rect(Rect::new(4, 4, 4, 4), &Luma::new([160]));
+        let embossed = emboss(&img, 0.);
+        assert_eq!(embossed.get_pixel(1, 6), &Luma::new([128]));
+        assert_eq!(embossed.get_pixel(5, 5), &Luma::new([128]));
+        // The left and right borders of the bright square are raised in opposite directions.
+        assert!(embossed.get_pixel(4, 6).data[0] > 200);
+        assert!(embossed.get_pixel(7, 6).data[0] < 50);
+
+        let vertical = emboss(&img, FRAC_PI_2);
+        assert_eq!(vertical.get_pixel(6, 1), &Luma::new([128]));
+        assert!(vertical.get_pixel(6, 4).data[0] > 200);
+        assert!(vertical.get_pixel(6, 7).data[0] < 50);
+
+        // Borders are replicated, so flat regions stay mid-gray up to the image edges in every direction.
+        let diagonal = emboss(&img, FRAC_PI_4);
+        for &(x, y) in &[(0, 0), (11, 0), (0, 11), (11, 11), (11, 6), (6, 11)] {
+            assert_eq!(diagonal.get_pixel(x, y), &Luma::new([128]));
+        }
+        assert!(diagonal.get_pixel(4, 4).data[0] > 200);
+        assert!(diagonal.get_pixel(7, 7).data[0] < 50);
+    }
+
+    #[test]
+    fn test_pixelate() {
+        let img = ImageBuffer2D::generate(10, 7, |(x, y)| Luma::new([(x * 10 + y) as f32]));
+        let pixelated = pixelate(&img, 4);
+        assert_eq!(pixelated.dimensions(), (10, 7));
+        for (rect, _) in img.tiles(4, 4) {
+            let n = (rect.width() * rect.height()) as f32;
+            let mean = img.rect_iter(rect).map(|p| p.data[0]).sum::<f32>() / n;
+            for p in pixelated.rect_iter(rect) {
+                assert!((p.data[0] - mean).abs() < 1e-4);
+            }
+        }
+        assert_eq!(pixelated.get_pixel(9, 6), &Luma::new([90.]));
+        assert_eq!(pixelate(&img, 1), img);
+    }
+
+    #[cfg(feature = "rand_integration")]
+    #[test]
+    fn test_nl_means() {
+        use rand::distributions::Normal;
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+
+        let clean = ImageBuffer2D::generate(40, 32, |(x, y)| {
+            let v = if (x / 10 + y / 8) % 2 == 0 { 60 } else { 190 };
+            Luma::new([v as u8])
+        });
+        let mut rng = StdRng::seed_from_u64(7);
+        let normal = Normal::new(0., 15.);
+        let noisy = map_pixels(&clean, |p| {
+            Luma::new([round_saturate(
+                f32::from(p.data[0]) + rng.sample(normal) as f32,
+            )])
+        });
+        let mse = |img: &ImageBuffer2D<Luma<u8>>| {
+            img.iter()
+                .zip(clean.iter())
+                .map(|(p, q)| (f32::from(p.data[0]) - f32::from(q.data[0])).powi(2))
+                .sum::<f32>()
+                / (40 * 32) as f32
+        };
+        let denoised = nl_means(&noisy, 25., 5, 11);
+        assert_eq!(denoised.dimensions(), (40, 32));
+        assert!(
+            mse(&denoised) < 0.25 * mse(&noisy),
+            "{} vs {}",
+            mse(&denoised),
+            mse(&noisy)
+        );
+
+        // A uniform image is left unchanged.
+        let uniform = ImageBuffer2D::generate(8, 8, |_| Luma::new([77u8]));
+        assert_eq!(nl_means(&uniform, 10., 3, 5), uniform);
+    }
+}
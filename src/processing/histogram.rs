@@ -3,6 +3,8 @@
 use core::{Image2D, ImageBuffer2D, Luma, Pixel};
 
 use num_traits::{NumCast, Zero};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 
 use std::convert::{From, Into};
 
@@ -48,12 +50,12 @@ impl Histogram {
     // fn draw(&self) -> Image2D<Rgb<u8>>
 }
 
-impl<'a, P> From<&'a Image2D<P>> for Histogram
+impl<'a, P> From<&'a dyn Image2D<P>> for Histogram
 where
     P: HistPixel,
 {
     /// Construct a Histogram from an image.
-    fn from(img: &'a Image2D<P>) -> Histogram {
+    fn from(img: &'a dyn Image2D<P>) -> Histogram {
         let mut v = [0; 256];
         for pix in img {
             let idx = <u8 as NumCast>::from::<P::Subpixel>(pix.channels()[0]).unwrap();
@@ -63,8 +65,41 @@ where
     }
 }
 
+/// Parallel version of the histogram computation of `Histogram::from`, distributing the rows of the image across
+/// threads and summing their partial histograms. The result is identical to the serial computation. Requires the
+/// `rayon` feature.
+#[cfg(feature = "rayon")]
+pub fn par_histogram<P>(img: &dyn Image2D<P>) -> Histogram
+where
+    P: HistPixel,
+{
+    let rows = img.rows().collect::<Vec<_>>();
+    let v = rows
+        .par_iter()
+        .fold(
+            || [0u32; 256],
+            |mut v, row| {
+                for pix in row {
+                    let idx = <u8 as NumCast>::from::<P::Subpixel>(pix.channels()[0]).unwrap();
+                    v[idx as usize] += 1;
+                }
+                v
+            },
+        )
+        .reduce(
+            || [0u32; 256],
+            |mut a, b| {
+                for (a, b) in a.iter_mut().zip(b.iter()) {
+                    *a += b;
+                }
+                a
+            },
+        );
+    Histogram { v }
+}
+
 /// Adjust the contrast of an image by histogram equalization.
-pub fn equalize<P>(img: &Image2D<P>) -> ImageBuffer2D<P>
+pub fn equalize<P>(img: &dyn Image2D<P>) -> ImageBuffer2D<P>
 where
     P: HistPixel,
 {
@@ -84,3 +119,24 @@ where
     }
     equalized
 }
+
+#[cfg(all(test, feature = "rayon"))]
+mod tests {
+    use super::*;
+    use core::Rect;
+
+    #[test]
+    fn test_par_histogram() {
+        let img = ImageBuffer2D::generate(1031, 769, |(x, y)| {
+            Luma::new([((x * 131 + y * 37 + x * y * 7) % 256) as u8])
+        });
+        let serial = Histogram::from(&img as &dyn Image2D<Luma<u8>>);
+        let parallel = par_histogram(&img);
+        assert_eq!(&parallel.bins()[..], &serial.bins()[..]);
+        assert_eq!(parallel.bins().iter().sum::<u32>(), 1031 * 769);
+
+        let sub = img.sub_image(Rect::new(17, 3, 500, 401));
+        let serial = Histogram::from(&sub as &dyn Image2D<Luma<u8>>);
+        assert_eq!(&par_histogram(&sub).bins()[..], &serial.bins()[..]);
+    }
+}
@@ -0,0 +1,132 @@
+//! Contains intensity transforms and normalizations of grayscale images.
+
+use core::{Image2D, ImageBuffer2D, Luma, Primitive};
+use processing::map::map_pixels;
+
+use num_traits::cast::cast;
+
+/// Standardize a grayscale image to zero mean and unit variance, i.e. map each pixel value `v` to
+/// `(v - mean) / std`, where `std` is the population standard deviation of the image. A constant image is mapped to
+/// zeros.
+pub fn standardize(img: &dyn Image2D<Luma<u8>>) -> ImageBuffer2D<Luma<f32>> {
+    let n = f64::from(img.width()) * f64::from(img.height());
+    let (sum, sum_sq) = img.iter().fold((0u64, 0u64), |(s, s2), p| {
+        let v = u64::from(p.data[0]);
+        (s + v, s2 + v * v)
+    });
+    let mean = if n > 0. { sum as f64 / n } else { 0. };
+    let variance = if n > 0. {
+        (sum_sq as f64 / n - mean * mean).max(0.)
+    } else {
+        0.
+    };
+    let std = variance.sqrt();
+    map_pixels(img, |p| {
+        if std > 0. {
+            Luma::new([((f64::from(p.data[0]) - mean) / std) as f32])
+        } else {
+            Luma::new([0.])
+        }
+    })
+}
+
+/// Linearly remap the values of a grayscale image so that its minimum is mapped to 0 and its maximum to 1. A constant
+/// image is mapped to zeros.
+pub fn normalize_minmax<P>(img: &dyn Image2D<Luma<P>>) -> ImageBuffer2D<Luma<f32>>
+where
+    P: Primitive,
+{
+    let to_f64 = |p: &Luma<P>| cast::<P, f64>(p.data[0]).unwrap();
+    let (min, max) = img
+        .iter()
+        .map(to_f64)
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+            (lo.min(v), hi.max(v))
+        });
+    let range = max - min;
+    map_pixels(img, |p| {
+        if range > 0. {
+            Luma::new([((to_f64(p) - min) / range) as f32])
+        } else {
+            Luma::new([0.])
+        }
+    })
+}
+
+/// Raise each pixel value of a floating point image to the power `exponent`. Exponents smaller than 1 expand the dark
+/// values and compress the bright ones, and conversely. Negative values result in NaN unless `exponent` is an integer.
+pub fn pow_transform(img: &dyn Image2D<Luma<f32>>, exponent: f32) -> ImageBuffer2D<Luma<f32>> {
+    map_pixels(img, |p| Luma::new([p.data[0].powf(exponent)]))
+}
+
+/// Map each pixel value `v` of a floating point image to `c * ln(1 + v)`, compressing the dynamic range of images with
+/// a few very bright values. Values smaller than or equal to -1 result in NaN or negative infinity.
+pub fn log_transform(img: &dyn Image2D<Luma<f32>>, c: f32) -> ImageBuffer2D<Luma<f32>> {
+    map_pixels(img, |p| Luma::new([c * p.data[0].ln_1p()]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standardize() {
+        let img = ImageBuffer2D::generate(13, 7, |(x, y)| Luma::new([(x * x + 3 * y) as u8]));
+        let standardized = standardize(&img);
+        let n = (13 * 7) as f32;
+        let mean = standardized.iter().map(|p| p.data[0]).sum::<f32>() / n;
+        let variance = standardized
+            .iter()
+            .map(|p| (p.data[0] - mean) * (p.data[0] - mean))
+            .sum::<f32>()
+            / n;
+        assert!(mean.abs() < 1e-5, "{}", mean);
+        assert!((variance - 1.).abs() < 1e-4, "{}", variance);
+        // The transform is increasing.
+        assert!(standardized.get_pixel(12, 0).data[0] > standardized.get_pixel(11, 0).data[0]);
+
+        let constant = ImageBuffer2D::generate(4, 4, |_| Luma::new([42u8]));
+        assert!(standardize(&constant).iter().all(|p| p.data[0] == 0.));
+    }
+
+    #[test]
+    fn test_normalize_minmax() {
+        let img = ImageBuffer2D::generate(9, 2, |(x, _)| Luma::new([20 + 10 * x as i16]));
+        let normalized = normalize_minmax(&img);
+        assert_eq!(normalized.get_pixel(0, 1), &Luma::new([0.]));
+        assert_eq!(normalized.get_pixel(8, 0), &Luma::new([1.]));
+        assert_eq!(normalized.get_pixel(2, 0), &Luma::new([0.25]));
+
+        let img = ImageBuffer2D::generate(5, 1, |(x, _)| Luma::new([-1. + x as f32 * 0.5]));
+        let normalized = normalize_minmax(&img);
+        assert_eq!(
+            normalized.iter().map(|p| p.data[0]).collect::<Vec<f32>>(),
+            vec![0., 0.25, 0.5, 0.75, 1.]
+        );
+
+        let constant = ImageBuffer2D::generate(4, 4, |_| Luma::new([7u8]));
+        assert!(normalize_minmax(&constant).iter().all(|p| p.data[0] == 0.));
+    }
+
+    #[test]
+    fn test_pow_log_transform() {
+        let img = ImageBuffer2D::generate(16, 3, |(x, y)| Luma::new([(x * x + y) as f32 * 0.37]));
+        assert_eq!(pow_transform(&img, 1.), img);
+        let squared = pow_transform(&img, 2.);
+        assert!((squared.get_pixel(3, 1).data[0] - 3.7 * 3.7).abs() < 1e-4);
+
+        let logged = log_transform(&img, 2.);
+        assert_eq!(logged.get_pixel(0, 0), &Luma::new([0.]));
+        assert!((logged.get_pixel(2, 0).data[0] - 2. * 2.48f32.ln()).abs() < 1e-5);
+        let values = logged
+            .iter()
+            .take(16)
+            .map(|p| p.data[0])
+            .collect::<Vec<f32>>();
+        for i in 1..values.len() {
+            assert!(values[i] > values[i - 1]);
+        }
+        // The input values are spaced increasingly, while the output ones get closer for high values.
+        assert!(values[15] - values[14] < values[2] - values[1]);
+    }
+}
@@ -37,7 +37,7 @@ where
     }
 
     /// Convolve an image with the kernel. Uses zero-padding for borders.
-    pub fn convolve<P, S>(&self, img: &Image2D<P>) -> ImageBuffer2D<P>
+    pub fn convolve<P, S>(&self, img: &dyn Image2D<P>) -> ImageBuffer2D<P>
     where
         P: Pixel<Subpixel = S> + Zero + Add,
         S: Primitive,
@@ -114,4 +114,37 @@ where
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+    use core::Luma;
+
+    #[test]
+    fn test_impulse_response() {
+        // Point symmetric kernel, whose convolution and correlation are identical.
+        let elems = vec![1., 2., 3., 4., 5., 4., 3., 2., 1.];
+        let kernel = Kernel::new(elems.clone(), 1).unwrap();
+        let response = kernel.convolve(&ImageBuffer2D::impulse(7, 7, 3, 3, 1.));
+        for ((y, x), p) in response.enumerate_pixels() {
+            let expected = if (2..5).contains(&x) && (2..5).contains(&y) {
+                elems[(y - 2) * 3 + x - 2]
+            } else {
+                0.
+            };
+            assert_eq!(p, &Luma::new([expected]));
+        }
+
+        let sigma = 1.2;
+        let impulse = ImageBuffer2D::impulse(15, 15, 7, 7, 1.);
+        let response = Kernel::gaussian(sigma, 3).convolve(&impulse);
+        let peak = 1. / (2. * ::std::f32::consts::PI * sigma * sigma);
+        let spot = ImageBuffer2D::gaussian_spot(15, 15, 7., 7., sigma, peak);
+        for ((y, x), p) in response.enumerate_pixels() {
+            let expected = if (4..11).contains(&x) && (4..11).contains(&y) {
+                spot.get_pixel(x as u32, y as u32).data[0]
+            } else {
+                0.
+            };
+            assert!((p.data[0] - expected).abs() < 1e-7, "{:?} {}", p, expected);
+        }
+    }
+}
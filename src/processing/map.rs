@@ -0,0 +1,67 @@
+//! Contains functions applying a transformation to each pixel of an image.
+
+use core::{Image2D, ImageBuffer2D, Pixel};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+/// Create a new image by applying `f` to each pixel of `img`. The output pixel type may differ from the input one.
+pub fn map_pixels<P, Q, F>(img: &dyn Image2D<P>, f: F) -> ImageBuffer2D<Q>
+where
+    P: Pixel,
+    Q: Pixel,
+    F: FnMut(&P) -> Q,
+{
+    let pixels = img.iter().map(f).collect();
+    ImageBuffer2D::from_vec(img.width(), img.height(), pixels).unwrap()
+}
+
+/// Parallel version of `map_pixels`, distributing the calls to `f` across threads. This is useful for expensive per
+/// pixel transforms. Requires the `rayon` feature.
+#[cfg(feature = "rayon")]
+pub fn par_map_pixels<P, Q, F>(img: &dyn Image2D<P>, f: F) -> ImageBuffer2D<Q>
+where
+    P: Pixel,
+    Q: Pixel,
+    F: Fn(&P) -> Q + Sync,
+{
+    let pixels = match img.as_slice() {
+        Some(slice) => slice.par_iter().map(&f).collect(),
+        // Non contiguous images, such as sub-images, are first gathered in row-major order.
+        None => img
+            .iter()
+            .collect::<Vec<&P>>()
+            .par_iter()
+            .map(|p| f(p))
+            .collect(),
+    };
+    ImageBuffer2D::from_vec(img.width(), img.height(), pixels).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "rayon")]
+    use core::Rect;
+    use core::{Luma, Rgb};
+
+    #[test]
+    fn test_map_pixels() {
+        let img = ImageBuffer2D::generate(5, 4, |(x, y)| Rgb::new([x as u8, y as u8, 10]));
+        let gray = map_pixels(&img, |p| Luma::new([p.data[0] as f32 + p.data[1] as f32]));
+        assert_eq!(gray.dimensions(), (5, 4));
+        assert_eq!(gray.get_pixel(3, 2), &Luma::new([5.]));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par_map_pixels() {
+        let img =
+            ImageBuffer2D::generate(37, 23, |(x, y)| Rgb::new([x as u8, y as u8, (x * y) as u8]));
+        let f = |p: &Rgb<u8>| Luma::new([p.data.iter().map(|c| f32::from(*c).sqrt()).sum::<f32>()]);
+        assert_eq!(par_map_pixels(&img, f), map_pixels(&img, f));
+
+        let sub = img.sub_image(Rect::new(3, 5, 20, 11));
+        assert_eq!(par_map_pixels(&sub, f), map_pixels(&sub, f));
+    }
+}
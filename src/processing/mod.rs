@@ -1,4 +1,19 @@
 //! Contains image processing operations.
 
+pub mod blend;
+pub mod color;
+pub mod features;
+#[cfg(feature = "fft")]
+pub mod fft;
+pub mod filter;
 pub mod histogram;
+pub mod intensity;
 pub mod kernel;
+pub mod map;
+pub mod morphology;
+pub mod motion;
+pub mod pipeline;
+pub mod rank;
+pub mod resize;
+pub mod segmentation;
+pub mod shape;
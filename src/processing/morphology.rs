@@ -0,0 +1,109 @@
+//! Contains morphological operations on binary images, where non-zero pixels are considered to be part of the
+//! foreground.
+
+use core::{Image2D, ImageBuffer2D, Luma};
+
+use failure::Error;
+
+/// A structuring element, i.e. a set of offsets relative to an origin located at the center of its mask.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructuringElement {
+    offsets: Vec<(i64, i64)>,
+}
+
+impl StructuringElement {
+    /// Create a structuring element from a `width x height` row-major mask, whose `true` elements belong to the
+    /// element. The origin is the element at `(width / 2, height / 2)`.
+    ///
+    /// Fails if the length of the mask does not match the dimensions.
+    pub fn new(width: u32, height: u32, mask: &[bool]) -> Result<StructuringElement, Error> {
+        let (w, h) = (width as usize, height as usize);
+        ensure!(
+            mask.len() == w * h,
+            "Mask length {} does not match the dimensions ({}, {})",
+            mask.len(),
+            width,
+            height
+        );
+        let offsets = mask
+            .iter()
+            .enumerate()
+            .filter(|&(_, &m)| m)
+            .map(|(i, _)| {
+                (
+                    (i % w) as i64 - (w / 2) as i64,
+                    (i / w) as i64 - (h / 2) as i64,
+                )
+            })
+            .collect();
+        Ok(StructuringElement { offsets })
+    }
+
+    /// Create a structuring element made of all the elements of a `width x height` rectangle.
+    pub fn rect(width: u32, height: u32) -> StructuringElement {
+        StructuringElement::new(width, height, &vec![true; (width * height) as usize]).unwrap()
+    }
+
+    /// Return the offsets `(dx, dy)` of the elements relative to the origin.
+    pub fn offsets(&self) -> &[(i64, i64)] {
+        &self.offsets
+    }
+}
+
+/// Compute the hit-or-miss transform of a binary image: a pixel is set to 255 if every element of `fg` falls on a
+/// foreground pixel and every element of `bg` falls on a background pixel when centered on it, and to 0 otherwise.
+/// Pixels outside of the image are considered to be background.
+pub fn hit_or_miss(
+    img: &dyn Image2D<Luma<u8>>,
+    fg: &StructuringElement,
+    bg: &StructuringElement,
+) -> ImageBuffer2D<Luma<u8>> {
+    let (w, h) = (i64::from(img.width()), i64::from(img.height()));
+    let is_fg = |x: i64, y: i64| {
+        x >= 0 && y >= 0 && x < w && y < h && img.get_pixel(x as u32, y as u32).data[0] != 0
+    };
+    ImageBuffer2D::generate(img.width(), img.height(), |(x, y)| {
+        let (x, y) = (i64::from(x), i64::from(y));
+        let hit = fg.offsets().iter().all(|&(dx, dy)| is_fg(x + dx, y + dy))
+            && bg.offsets().iter().all(|&(dx, dy)| !is_fg(x + dx, y + dy));
+        Luma::new([if hit { 255 } else { 0 }])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::{Image2DMut, Rect};
+
+    #[test]
+    fn test_structuring_element() {
+        let se = StructuringElement::new(3, 2, &[false, true, false, true, true, false]).unwrap();
+        assert_eq!(se.offsets(), &[(0, -1), (-1, 0), (0, 0)]);
+        assert!(StructuringElement::new(3, 3, &[true; 8]).is_err());
+        assert_eq!(
+            StructuringElement::rect(3, 1).offsets(),
+            &[(-1, 0), (0, 0), (1, 0)]
+        );
+    }
+
+    #[test]
+    fn test_hit_or_miss() {
+        let mut img = ImageBuffer2D::<Luma<u8>>::new(16, 10);
+        img.fill_rect(Rect::new(3, 5, 8, 1), &Luma::new([255]));
+
+        // The right end point of a horizontal segment has a foreground pixel on its left and nothing on its right.
+        let (t, f) = (true, false);
+        let fg = StructuringElement::new(3, 3, &[f, f, f, t, t, f, f, f, f]).unwrap();
+        let bg = StructuringElement::new(3, 3, &[f, f, t, f, f, t, f, f, t]).unwrap();
+        let hits = hit_or_miss(&img, &fg, &bg);
+        for ((y, x), p) in hits.enumerate_pixels() {
+            assert_eq!(p.data[0] == 255, (x, y) == (10, 5), "pixel ({}, {})", x, y);
+        }
+
+        let fg = StructuringElement::new(3, 3, &[f, f, f, f, t, t, f, f, f]).unwrap();
+        let bg = StructuringElement::new(3, 3, &[t, f, f, t, f, f, t, f, f]).unwrap();
+        let hits = hit_or_miss(&img, &fg, &bg);
+        assert_eq!(hits.iter().filter(|p| p.data[0] == 255).count(), 1);
+        assert_eq!(hits.get_pixel(3, 5), &Luma::new([255]));
+    }
+}
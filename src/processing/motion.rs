@@ -0,0 +1,245 @@
+//! Contains motion estimation algorithms between consecutive frames.
+
+use core::{ChannelPixel, Image2D, ImageBuffer2D, Luma};
+use processing::filter::gradients;
+use processing::map::map_pixels;
+use processing::resize::{sample, ResizeFilter};
+use processing::shape::region_moments;
+
+// Number of Gauss-Newton iterations performed by the Lucas-Kanade tracker.
+const LK_ITERATIONS: u32 = 10;
+
+// Minimum eigenvalue of the window-averaged structure tensor for a point to be trackable.
+const LK_MIN_EIGENVALUE: f32 = 0.1;
+
+/// Estimate the displacement of each point of `points` between `prev` and `next` with the Lucas-Kanade method, by
+/// iteratively solving the least-squares optical flow system over a `window x window` neighborhood of the point.
+/// Neighborhoods are sampled bilinearly, so points and displacements may be fractional. The method assumes small
+/// displacements, of the order of the window radius at most.
+///
+/// Returns `None` for points outside of `prev`, and for points whose neighborhood lacks texture in two directions,
+/// for which the system is ill-conditioned.
+///
+/// **Panics** if the images do not have the same dimensions.
+pub fn lucas_kanade(
+    prev: &dyn Image2D<Luma<u8>>,
+    next: &dyn Image2D<Luma<u8>>,
+    points: &[(f32, f32)],
+    window: u32,
+) -> Vec<Option<(f32, f32)>> {
+    assert_eq!(
+        prev.dimensions(),
+        next.dimensions(),
+        "Image dimensions do not match."
+    );
+    let (w, h) = prev.dimensions();
+    let (gx, gy) = gradients(prev);
+    let to_f32 = |p: &Luma<u8>| Luma::new([f32::from(p.data[0])]);
+    let (prev, next) = (map_pixels(prev, to_f32), map_pixels(next, to_f32));
+    let at = |img: &ImageBuffer2D<Luma<f32>>, x: f32, y: f32| {
+        sample(img, x, y, ResizeFilter::Bilinear).data[0]
+    };
+    let radius = (window / 2) as i32;
+    let offsets = (-radius..=radius)
+        .flat_map(|dy| (-radius..=radius).map(move |dx| (dx as f32, dy as f32)))
+        .collect::<Vec<_>>();
+
+    points
+        .iter()
+        .map(|&(px, py)| {
+            if px < 0. || py < 0. || px > (w as f32 - 1.) || py > (h as f32 - 1.) {
+                return None;
+            }
+            // Structure tensor of the window, which does not depend on the displacement.
+            let samples = offsets
+                .iter()
+                .map(|&(dx, dy)| {
+                    let (x, y) = (px + dx, py + dy);
+                    (x, y, at(&gx, x, y), at(&gy, x, y), at(&prev, x, y))
+                })
+                .collect::<Vec<_>>();
+            let (mut gxx, mut gxy, mut gyy) = (0., 0., 0.);
+            for &(_, _, ix, iy, _) in &samples {
+                gxx += ix * ix;
+                gxy += ix * iy;
+                gyy += iy * iy;
+            }
+            let n = samples.len() as f32;
+            let (mean, det) = ((gxx + gyy) / 2., gxx * gyy - gxy * gxy);
+            let min_eigenvalue = mean - (mean * mean - det).max(0.).sqrt();
+            if min_eigenvalue / n < LK_MIN_EIGENVALUE {
+                return None;
+            }
+
+            let (mut u, mut v) = (0f32, 0f32);
+            for _ in 0..LK_ITERATIONS {
+                let (mut bx, mut by) = (0., 0.);
+                for &(x, y, ix, iy, p) in &samples {
+                    let it = at(&next, x + u, y + v) - p;
+                    bx -= ix * it;
+                    by -= iy * it;
+                }
+                let du = (gyy * bx - gxy * by) / det;
+                let dv = (gxx * by - gxy * bx) / det;
+                u += du;
+                v += dv;
+                if du * du + dv * dv < 1e-6 {
+                    break;
+                }
+            }
+            Some((u, v))
+        })
+        .collect()
+}
+
+/// Estimate the motion between `prev` and `next` by block matching: `prev` is divided into `block x block` blocks, and
+/// each block is searched for in `next` at every displacement of at most `search` pixels along each axis, keeping the
+/// one minimizing the sum of absolute differences. Displacements moving the block out of `next` are not considered,
+/// and ties are resolved in favor of the smallest displacement. Blocks on the right and bottom edges are smaller if
+/// the image dimensions are not multiples of `block`.
+///
+/// Returns an image with one pixel per block, holding its displacement `[dx, dy]` from `prev` to `next`.
+///
+/// **Panics** if the images do not have the same dimensions or if `block` is zero.
+pub fn block_match(
+    prev: &dyn Image2D<Luma<u8>>,
+    next: &dyn Image2D<Luma<u8>>,
+    block: u32,
+    search: u32,
+) -> ImageBuffer2D<ChannelPixel<i32, 2>> {
+    assert_eq!(
+        prev.dimensions(),
+        next.dimensions(),
+        "Image dimensions do not match."
+    );
+    let (w, h) = (i64::from(prev.width()), i64::from(prev.height()));
+    let search = i64::from(search);
+    let mut vectors = Vec::new();
+    for (rect, tile) in prev.tiles(block, block) {
+        let (bx, by) = (i64::from(rect.left()), i64::from(rect.top()));
+        let (bw, bh) = (i64::from(rect.width()), i64::from(rect.height()));
+        let mut best = (u64::MAX, 0, [0, 0]);
+        for dy in -search..=search {
+            for dx in -search..=search {
+                let (x0, y0) = (bx + dx, by + dy);
+                if x0 < 0 || y0 < 0 || x0 + bw > w || y0 + bh > h {
+                    continue;
+                }
+                let mut sad = 0u64;
+                for ((y, x), p) in tile.enumerate_pixels() {
+                    let q = next.get_pixel((x0 + x as i64) as u32, (y0 + y as i64) as u32);
+                    sad += u64::from((i16::from(p.data[0]) - i16::from(q.data[0])).unsigned_abs());
+                }
+                let candidate = (sad, dx * dx + dy * dy, [dx as i32, dy as i32]);
+                if (candidate.0, candidate.1) < (best.0, best.1) {
+                    best = candidate;
+                }
+            }
+        }
+        vectors.push(ChannelPixel::new(best.2));
+    }
+    let (nx, ny) = (prev.width().div_ceil(block), prev.height().div_ceil(block));
+    ImageBuffer2D::from_vec(nx, ny, vectors).unwrap()
+}
+
+/// Estimate the translation aligning `moving` onto `fixed` by matching the centroids of their regions of non-zero
+/// pixels, as computed by `region_moments`. This is a cheap coarse registration step, accurate when both images
+/// contain the same object on a null background.
+///
+/// Returns the displacement `(dx, dy)`, rounded to the closest integers, to apply to `moving`, or `(0, 0)` if one of
+/// the images has no non-zero pixel.
+pub fn align_by_centroid(
+    moving: &dyn Image2D<Luma<u8>>,
+    fixed: &dyn Image2D<Luma<u8>>,
+) -> (i64, i64) {
+    match (
+        region_moments(moving).centroid(),
+        region_moments(fixed).centroid(),
+    ) {
+        (Some((mx, my)), Some((fx, fy))) => ((fx - mx).round() as i64, (fy - my).round() as i64),
+        _ => (0, 0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Smooth synthetic scene made of gaussian blobs, translated by `(dx, dy)`.
+    fn blobs(dx: f32, dy: f32) -> ImageBuffer2D<Luma<u8>> {
+        let centers = [(12., 14.), (30., 10.), (22., 30.), (40., 34.)];
+        ImageBuffer2D::generate(52, 44, |(x, y)| {
+            let (x, y) = (x as f32 - dx, y as f32 - dy);
+            let v = centers.iter().fold(20., |acc, &(cx, cy)| {
+                let d2 = (x - cx) * (x - cx) + (y - cy) * (y - cy);
+                acc + 200. * (-d2 / 18.).exp()
+            });
+            Luma::new([v.round() as u8])
+        })
+    }
+
+    #[test]
+    fn test_lucas_kanade() {
+        let (dx, dy) = (0.8, -0.6);
+        let (prev, next) = (blobs(0., 0.), blobs(dx, dy));
+        let points = [(12., 14.), (32., 11.), (21., 29.5), (40., 34.)];
+        for flow in lucas_kanade(&prev, &next, &points, 9) {
+            let (u, v) = flow.unwrap();
+            assert!((u - dx).abs() < 0.05 && (v - dy).abs() < 0.05, "{:?}", flow);
+        }
+
+        // Flat regions and points outside of the image cannot be tracked.
+        let flows = lucas_kanade(&prev, &next, &[(48., 2.), (-1., 3.), (10., 60.)], 5);
+        assert_eq!(flows, vec![None, None, None]);
+    }
+
+    #[test]
+    fn test_block_match() {
+        // Pseudo-random texture, translated by (3, -2).
+        let texture = |x: i64, y: i64| {
+            Luma::new([((x * 7919 + y * 104_729) ^ (x * y)).rem_euclid(251) as u8])
+        };
+        let prev = ImageBuffer2D::generate(60, 44, |(x, y)| texture(i64::from(x), i64::from(y)));
+        let next =
+            ImageBuffer2D::generate(60, 44, |(x, y)| texture(i64::from(x) - 3, i64::from(y) + 2));
+        let vectors = block_match(&prev, &next, 8, 4);
+        assert_eq!(vectors.dimensions(), (8, 6));
+        // Blocks whose content leaves the image cannot be matched.
+        for y in 1..5 {
+            for x in 0..7 {
+                assert_eq!(
+                    vectors.get_pixel(x, y),
+                    &ChannelPixel::new([3, -2]),
+                    "block ({}, {})",
+                    x,
+                    y
+                );
+            }
+        }
+
+        let same = block_match(&prev, &prev, 16, 2);
+        assert!(same.iter().all(|v| v == &ChannelPixel::new([0, 0])));
+    }
+
+    #[test]
+    fn test_align_by_centroid() {
+        // An L-shaped object, drawn at a given position.
+        let draw = |dx: i64, dy: i64| {
+            ImageBuffer2D::generate(40, 30, move |(x, y)| {
+                let (x, y) = (i64::from(x) - dx, i64::from(y) - dy);
+                let inside = (5..9).contains(&x) && (4..16).contains(&y)
+                    || (5..15).contains(&x) && (12..16).contains(&y);
+                Luma::new([if inside { 200u8 } else { 0 }])
+            })
+        };
+        let fixed = draw(0, 0);
+        let moving = draw(13, 7);
+        assert_eq!(align_by_centroid(&moving, &fixed), (-13, -7));
+        assert_eq!(align_by_centroid(&fixed, &moving), (13, 7));
+        assert_eq!(align_by_centroid(&fixed, &fixed), (0, 0));
+        assert_eq!(
+            align_by_centroid(&ImageBuffer2D::new(40, 30), &fixed),
+            (0, 0)
+        );
+    }
+}
@@ -0,0 +1,135 @@
+//! Contains a builder chaining image processing operations.
+
+use core::{Image2D, Image2DMut, ImageBuffer2D, Pixel};
+use processing::filter::gaussian_blur;
+use processing::resize::{resize, ResizeFilter};
+
+use num_traits::{Bounded, Zero};
+
+// A single operation of a pipeline.
+type Step<P> = Box<dyn Fn(ImageBuffer2D<P>) -> ImageBuffer2D<P>>;
+
+/// A sequence of operations applied in order to an image, built by chaining the methods of the builder. For example,
+/// `Pipeline::new().blur(1.5).resize(32, 24, ResizeFilter::Bilinear)` blurs an image then downsizes it, without
+/// binding the intermediate image.
+pub struct Pipeline<P>
+where
+    P: Pixel,
+{
+    steps: Vec<Step<P>>,
+}
+
+impl<P> Pipeline<P>
+where
+    P: Pixel + 'static,
+{
+    /// Create an empty pipeline, which returns a copy of its input.
+    pub fn new() -> Pipeline<P> {
+        Pipeline { steps: Vec::new() }
+    }
+
+    /// Append an arbitrary operation to the pipeline.
+    pub fn then<F>(mut self, f: F) -> Pipeline<P>
+    where
+        F: Fn(ImageBuffer2D<P>) -> ImageBuffer2D<P> + 'static,
+    {
+        self.steps.push(Box::new(f));
+        self
+    }
+
+    /// Append a resize to the given dimensions, see `resize`. The image is resampled without gamma correction.
+    pub fn resize(self, new_w: u32, new_h: u32, filter: ResizeFilter) -> Pipeline<P> {
+        self.then(move |img| resize(&img, new_w, new_h, filter, false))
+    }
+
+    /// Append a gaussian blur of standard deviation `sigma`, see `gaussian_blur`.
+    pub fn blur(self, sigma: f32) -> Pipeline<P> {
+        self.then(move |img| gaussian_blur(&img, sigma))
+    }
+
+    /// Append a threshold, setting each subpixel greater than `threshold` to the maximum value of its type and the
+    /// others to zero.
+    pub fn threshold(self, threshold: P::Subpixel) -> Pipeline<P> {
+        self.then(move |mut img| {
+            for p in img.iter_mut() {
+                *p = p.map(|c| {
+                    if c > threshold {
+                        P::Subpixel::max_value()
+                    } else {
+                        P::Subpixel::zero()
+                    }
+                });
+            }
+            img
+        })
+    }
+
+    /// Return the number of operations of the pipeline.
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Return `true` if the pipeline contains no operation.
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Apply the operations of the pipeline in order to a copy of `img`, and return the result.
+    pub fn apply(&self, img: &dyn Image2D<P>) -> ImageBuffer2D<P> {
+        self.steps
+            .iter()
+            .fold(img.to_owned(), |img, step| step(img))
+    }
+}
+
+impl<P> Default for Pipeline<P>
+where
+    P: Pixel + 'static,
+{
+    fn default() -> Pipeline<P> {
+        Pipeline::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::{Luma, Rgb};
+
+    #[test]
+    fn test_pipeline() {
+        let img = ImageBuffer2D::generate(40, 30, |(x, y)| {
+            Rgb::new([(x * 6) as u8, (y * 8) as u8, ((x + y) * 3) as u8])
+        });
+        let pipeline = Pipeline::new()
+            .blur(1.2)
+            .resize(20, 15, ResizeFilter::Bilinear);
+        assert_eq!(pipeline.len(), 2);
+        let expected = resize(
+            &gaussian_blur(&img, 1.2),
+            20,
+            15,
+            ResizeFilter::Bilinear,
+            false,
+        );
+        assert_eq!(pipeline.apply(&img), expected);
+
+        // Operations are applied in order.
+        let flipped = Pipeline::new()
+            .resize(20, 15, ResizeFilter::Bilinear)
+            .blur(1.2);
+        assert_ne!(flipped.apply(&img), expected);
+
+        let gray = ImageBuffer2D::generate(4, 1, |(x, _)| Luma::new([(x * 60) as u8]));
+        let binary = Pipeline::new()
+            .threshold(100)
+            .then(|img| resize(&img, 8, 1, ResizeFilter::Nearest, false))
+            .apply(&gray);
+        assert_eq!(
+            binary.iter().map(|p| p.data[0]).collect::<Vec<u8>>(),
+            vec![0, 0, 0, 0, 255, 255, 255, 255]
+        );
+        assert!(Pipeline::<Luma<u8>>::default().is_empty());
+        assert_eq!(Pipeline::new().apply(&gray), gray);
+    }
+}
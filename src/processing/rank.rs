@@ -0,0 +1,273 @@
+//! Contains rank filters, such as the minimum, maximum and median filters.
+
+use core::{Image2D, ImageBuffer2D, Luma};
+
+use std::cmp::{max, min};
+
+// Compute the running `op` (either min or max) over windows of `2 * radius + 1` elements centered on each element of
+// `line`, using the van Herk/Gil-Werman algorithm. Elements falling out of the line are replaced by the closest border
+// element.
+fn van_herk<F>(line: &[u8], radius: usize, op: F) -> Vec<u8>
+where
+    F: Fn(u8, u8) -> u8,
+{
+    let k = 2 * radius + 1;
+    let n = line.len();
+    let padded = (0..n + 2 * radius)
+        .map(|i| line[min(i.saturating_sub(radius), n - 1)])
+        .collect::<Vec<u8>>();
+
+    // Prefix and suffix accumulations within consecutive blocks of `k` elements.
+    let mut prefix = padded.clone();
+    let mut suffix = padded.clone();
+    for block in (0..padded.len()).step_by(k) {
+        let end = min(block + k, padded.len());
+        for i in block + 1..end {
+            prefix[i] = op(prefix[i - 1], padded[i]);
+        }
+        for i in (block..end - 1).rev() {
+            suffix[i] = op(suffix[i + 1], padded[i]);
+        }
+    }
+
+    // The window starting at `i` spans the end of one block and the start of the next one.
+    (0..n).map(|i| op(suffix[i], prefix[i + k - 1])).collect()
+}
+
+// Apply a separable running filter with a square window of the given radius.
+fn separable_rank<F>(img: &dyn Image2D<Luma<u8>>, radius: u32, op: F) -> ImageBuffer2D<Luma<u8>>
+where
+    F: Fn(u8, u8) -> u8,
+{
+    let (w, h) = img.dimensions();
+    if w == 0 || h == 0 {
+        return img.to_owned();
+    }
+    let radius = radius as usize;
+    let mut tmp = Vec::with_capacity(w as usize * h as usize);
+    for row in img.rows() {
+        let row = row.iter().map(|p| p.data[0]).collect::<Vec<u8>>();
+        tmp.extend(van_herk(&row, radius, &op));
+    }
+    let mut out = vec![0u8; tmp.len()];
+    let mut col = Vec::with_capacity(h as usize);
+    for x in 0..w as usize {
+        col.clear();
+        col.extend(tmp.iter().skip(x).step_by(w as usize));
+        for (y, v) in van_herk(&col, radius, &op).into_iter().enumerate() {
+            out[y * w as usize + x] = v;
+        }
+    }
+    ImageBuffer2D::from_raw_vec(w, h, &out).unwrap()
+}
+
+/// Compute the minimum of the `(2 * radius + 1) x (2 * radius + 1)` window centered on each pixel, which is the
+/// grayscale erosion by a square structuring element. Runs in constant time per pixel regardless of the radius. Pixels
+/// falling out of the image are replaced by the closest border pixel.
+pub fn min_filter(img: &dyn Image2D<Luma<u8>>, radius: u32) -> ImageBuffer2D<Luma<u8>> {
+    separable_rank(img, radius, min)
+}
+
+/// Compute the maximum of the `(2 * radius + 1) x (2 * radius + 1)` window centered on each pixel, which is the
+/// grayscale dilation by a square structuring element. Runs in constant time per pixel regardless of the radius. Pixels
+/// falling out of the image are replaced by the closest border pixel.
+pub fn max_filter(img: &dyn Image2D<Luma<u8>>, radius: u32) -> ImageBuffer2D<Luma<u8>> {
+    separable_rank(img, radius, max)
+}
+
+/// Compute the given percentile of the `(2 * radius + 1) x (2 * radius + 1)` window centered on each pixel. `rank` is
+/// clamped to `[0, 1]`: 0 selects the minimum, 0.5 the median and 1 the maximum of each window. Pixels falling out of
+/// the image are replaced by the closest border pixel.
+pub fn rank_filter(img: &dyn Image2D<Luma<u8>>, radius: u32, rank: f32) -> ImageBuffer2D<Luma<u8>> {
+    let (w, h) = img.dimensions();
+    if w == 0 || h == 0 {
+        return img.to_owned();
+    }
+    let r = i64::from(radius);
+    let d = 2 * r + 1;
+    let target = (rank.clamp(0., 1.) * (d * d - 1) as f32).round() as u32;
+    let value = |x: i64, y: i64| {
+        let x = min(max(x, 0), i64::from(w) - 1) as u32;
+        let y = min(max(y, 0), i64::from(h) - 1) as u32;
+        img.get_pixel(x, y).data[0] as usize
+    };
+
+    let mut out = Vec::with_capacity(w as usize * h as usize);
+    for y in 0..i64::from(h) {
+        // Sliding histogram of the current window, updated column by column.
+        let mut hist = [0u32; 256];
+        for dy in -r..=r {
+            for dx in -r..=r {
+                hist[value(dx, y + dy)] += 1;
+            }
+        }
+        for x in 0..i64::from(w) {
+            if x > 0 {
+                for dy in -r..=r {
+                    hist[value(x - r - 1, y + dy)] -= 1;
+                    hist[value(x + r, y + dy)] += 1;
+                }
+            }
+            let mut count = 0;
+            let selected = hist
+                .iter()
+                .position(|c| {
+                    count += c;
+                    count > target
+                })
+                .unwrap();
+            out.push(selected as u8);
+        }
+    }
+    ImageBuffer2D::from_raw_vec(w, h, &out).unwrap()
+}
+
+/// Compute the median of the `(2 * radius + 1) x (2 * radius + 1)` window centered on each pixel, using the
+/// Perreault-Hebert algorithm: a histogram is maintained for each column of the window height, and the window
+/// histogram is updated by adding and removing whole column histograms. This runs in constant time per pixel
+/// regardless of the radius, and gives the same result as `rank_filter` with a rank of 0.5. Pixels falling out of the
+/// image are replaced by the closest border pixel.
+pub fn median_filter_u8(img: &dyn Image2D<Luma<u8>>, radius: u32) -> ImageBuffer2D<Luma<u8>> {
+    let (w, h) = img.dimensions();
+    if w == 0 || h == 0 {
+        return img.to_owned();
+    }
+    let r = i64::from(radius);
+    let d = 2 * r + 1;
+    let target = ((d * d) / 2) as u32;
+    let clamp_x = |x: i64| min(max(x, 0), i64::from(w) - 1) as usize;
+    let clamp_y = |y: i64| min(max(y, 0), i64::from(h) - 1) as u32;
+    let value = |x: usize, y: u32| img.get_pixel(x as u32, y).data[0] as usize;
+
+    // Histograms of the `2 * radius + 1` pixels of each column centered on the current row.
+    let mut columns = vec![[0u32; 256]; w as usize];
+    for (x, column) in columns.iter_mut().enumerate() {
+        for dy in -r..=r {
+            column[value(x, clamp_y(dy))] += 1;
+        }
+    }
+
+    let mut out = Vec::with_capacity(w as usize * h as usize);
+    for y in 0..i64::from(h) {
+        if y > 0 {
+            for (x, column) in columns.iter_mut().enumerate() {
+                column[value(x, clamp_y(y - r - 1))] -= 1;
+                column[value(x, clamp_y(y + r))] += 1;
+            }
+        }
+        let mut hist = [0u32; 256];
+        for dx in -r..=r {
+            for (bin, c) in hist.iter_mut().zip(columns[clamp_x(dx)].iter()) {
+                *bin += c;
+            }
+        }
+        for x in 0..i64::from(w) {
+            if x > 0 {
+                let (removed, added) = (&columns[clamp_x(x - r - 1)], &columns[clamp_x(x + r)]);
+                for ((bin, add), sub) in hist.iter_mut().zip(added.iter()).zip(removed.iter()) {
+                    *bin = *bin + add - sub;
+                }
+            }
+            let mut count = 0;
+            let median = hist
+                .iter()
+                .position(|c| {
+                    count += c;
+                    count > target
+                })
+                .unwrap();
+            out.push(median as u8);
+        }
+    }
+    ImageBuffer2D::from_raw_vec(w, h, &out).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::Image2DMut;
+
+    fn naive_window(
+        img: &ImageBuffer2D<Luma<u8>>,
+        radius: u32,
+        op: fn(u8, u8) -> u8,
+    ) -> ImageBuffer2D<Luma<u8>> {
+        let (w, h) = img.dimensions();
+        let r = i64::from(radius);
+        ImageBuffer2D::generate(w, h, |(x, y)| {
+            let mut acc = img.get_pixel(x, y).data[0];
+            for dy in -r..=r {
+                for dx in -r..=r {
+                    let sx = min(max(i64::from(x) + dx, 0), i64::from(w) - 1) as u32;
+                    let sy = min(max(i64::from(y) + dy, 0), i64::from(h) - 1) as u32;
+                    acc = op(acc, img.get_pixel(sx, sy).data[0]);
+                }
+            }
+            Luma::new([acc])
+        })
+    }
+
+    #[test]
+    fn test_max_filter() {
+        let img = ImageBuffer2D::generate(13, 9, |(x, y)| {
+            Luma::new([((x * 97 + y * 61 + x * y * 13) % 251) as u8])
+        });
+        for radius in 0..6 {
+            assert_eq!(max_filter(&img, radius), naive_window(&img, radius, max));
+            assert_eq!(min_filter(&img, radius), naive_window(&img, radius, min));
+        }
+
+        let mut spot = ImageBuffer2D::generate(9, 9, |_| Luma::new([10u8]));
+        spot.put_pixel(4, 4, Luma::new([200]));
+        let grown = max_filter(&spot, 1);
+        assert_eq!(grown.iter().filter(|p| p.data[0] == 200).count(), 9);
+        assert_eq!(min_filter(&grown, 1), spot);
+    }
+
+    #[test]
+    fn test_rank_filter() {
+        let img = ImageBuffer2D::generate(11, 8, |(x, y)| {
+            Luma::new([((x * 89 + y * 53 + x * y * 7) % 256) as u8])
+        });
+        for radius in 0..4 {
+            assert_eq!(rank_filter(&img, radius, 0.), min_filter(&img, radius));
+            assert_eq!(rank_filter(&img, radius, 1.), max_filter(&img, radius));
+        }
+        assert_eq!(rank_filter(&img, 0, 0.5), img);
+
+        // A single outlier is removed by the median.
+        let mut spot = ImageBuffer2D::generate(7, 7, |_| Luma::new([50u8]));
+        spot.put_pixel(3, 3, Luma::new([255]));
+        assert!(rank_filter(&spot, 1, 0.5)
+            .iter()
+            .all(|p| p == &Luma::new([50])));
+    }
+
+    #[test]
+    #[cfg(feature = "rand_integration")]
+    fn test_median_filter_u8() {
+        let img = ImageBuffer2D::<Luma<u8>>::rand_seeded(23, 17, 11);
+        let (w, h) = img.dimensions();
+        let r = 3i64;
+        let sorted_median = ImageBuffer2D::generate(w, h, |(x, y)| {
+            let mut window = Vec::new();
+            for dy in -r..=r {
+                for dx in -r..=r {
+                    let sx = min(max(i64::from(x) + dx, 0), i64::from(w) - 1) as u32;
+                    let sy = min(max(i64::from(y) + dy, 0), i64::from(h) - 1) as u32;
+                    window.push(img.get_pixel(sx, sy).data[0]);
+                }
+            }
+            window.sort_unstable();
+            Luma::new([window[window.len() / 2]])
+        });
+        assert_eq!(median_filter_u8(&img, 3), sorted_median);
+
+        for radius in 0..12 {
+            assert_eq!(
+                median_filter_u8(&img, radius),
+                rank_filter(&img, radius, 0.5)
+            );
+        }
+    }
+}
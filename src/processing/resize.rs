@@ -0,0 +1,590 @@
+//! Contains image resizing and resampling functions.
+
+use core::{Image2D, ImageBuffer2D, Luma, Pixel, Rgb};
+use helper::generic::{round_saturate, srgb_decode, srgb_encode};
+use processing::map::map_pixels;
+
+use num_traits::{Bounded, NumCast, Zero};
+
+use std::cmp::{max, min};
+use std::f32::consts::PI;
+
+/// Interpolation filter used when resampling an image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResizeFilter {
+    /// Nearest neighbor interpolation.
+    Nearest,
+    /// Bilinear interpolation.
+    Bilinear,
+    /// Bicubic interpolation, using the Catmull-Rom spline.
+    Bicubic,
+    /// Separable Lanczos resampling with a support of 3 pixels. The filter is widened when downscaling to avoid
+    /// aliasing.
+    Lanczos3,
+}
+
+// Catmull-Rom cubic convolution kernel.
+fn catmull_rom(t: f32) -> f32 {
+    let t = t.abs();
+    if t < 1. {
+        1.5 * t * t * t - 2.5 * t * t + 1.
+    } else if t < 2. {
+        -0.5 * t * t * t + 2.5 * t * t - 4. * t + 2.
+    } else {
+        0.
+    }
+}
+
+// Normalized sinc function.
+fn sinc(t: f32) -> f32 {
+    if t == 0. {
+        1.
+    } else {
+        let a = t * PI;
+        a.sin() / a
+    }
+}
+
+// Lanczos kernel with a support of 3.
+fn lanczos3(t: f32) -> f32 {
+    if t.abs() < 3. {
+        sinc(t) * sinc(t / 3.)
+    } else {
+        0.
+    }
+}
+
+// Compute the source coordinates and weights used to interpolate at coordinate `x` along one axis.
+fn taps(x: f32, filter: ResizeFilter) -> Vec<(i64, f32)> {
+    let x0 = x.floor();
+    let fx = x - x0;
+    let x0 = x0 as i64;
+    match filter {
+        ResizeFilter::Nearest => vec![(x.round() as i64, 1.)],
+        ResizeFilter::Bilinear => vec![(x0, 1. - fx), (x0 + 1, fx)],
+        ResizeFilter::Bicubic => (-1..3)
+            .map(|i| (x0 + i, catmull_rom(fx - i as f32)))
+            .collect(),
+        ResizeFilter::Lanczos3 => (-2..4).map(|i| (x0 + i, lanczos3(fx - i as f32))).collect(),
+    }
+}
+
+/// Sample an image at the given, possibly fractional, coordinates using the specified interpolation filter. Pixels
+/// falling out of the image are replaced by the closest border pixel.
+///
+/// **Panics** if the image is empty.
+pub fn sample<P>(img: &dyn Image2D<P>, x: f32, y: f32, filter: ResizeFilter) -> P
+where
+    P: Pixel,
+{
+    let (w, h) = (img.width() as i64, img.height() as i64);
+    assert!(w > 0 && h > 0, "Cannot sample an empty image.");
+    let n_channels = P::N_CHANNELS as usize;
+    let mut accu = vec![0f32; n_channels];
+    let taps_y = taps(y, filter);
+    for (sx, wx) in taps(x, filter) {
+        let sx = min(max(sx, 0), w - 1) as u32;
+        for &(sy, wy) in &taps_y {
+            let sy = min(max(sy, 0), h - 1) as u32;
+            let pix = img.get_pixel(sx, sy);
+            for (a, c) in accu.iter_mut().zip(pix.channels()) {
+                *a += wx * wy * <f32 as NumCast>::from(*c).unwrap();
+            }
+        }
+    }
+    let channels = accu
+        .into_iter()
+        .map(round_saturate)
+        .collect::<Vec<P::Subpixel>>();
+    P::from_slice(&channels)
+}
+
+/// Sample an intensity profile along the segment from `p0` to `p1`, at `samples` evenly spaced points including both
+/// endpoints. Pixels are interpolated bilinearly, and points falling out of the image are clamped to the border.
+///
+/// **Panics** if the image is empty and `samples` is not zero.
+pub fn line_profile<P>(
+    img: &dyn Image2D<P>,
+    p0: (f32, f32),
+    p1: (f32, f32),
+    samples: usize,
+) -> Vec<P>
+where
+    P: Pixel,
+{
+    let step = if samples > 1 {
+        1. / (samples - 1) as f32
+    } else {
+        0.
+    };
+    (0..samples)
+        .map(|i| {
+            let t = i as f32 * step;
+            let x = p0.0 + t * (p1.0 - p0.0);
+            let y = p0.1 + t * (p1.1 - p0.1);
+            sample(img, x, y, ResizeFilter::Bilinear)
+        })
+        .collect()
+}
+
+/// Resize an image to the given dimensions using the specified interpolation filter.
+///
+/// If `gamma_correct` is `true`, the image is assumed to be sRGB encoded and is resampled in linear light, which avoids
+/// the darkening of fine bright details when downscaling. Integer channels are normalized by the maximum value of
+/// their type and floating point channels are expected to be in `[0, 1]`. The alpha channel of two and four channel
+/// pixels is resampled as is.
+///
+/// **Panics** if the source image is empty and the requested dimensions are not.
+pub fn resize<P>(
+    img: &dyn Image2D<P>,
+    new_w: u32,
+    new_h: u32,
+    filter: ResizeFilter,
+    gamma_correct: bool,
+) -> ImageBuffer2D<P>
+where
+    P: Pixel,
+{
+    if gamma_correct {
+        return resize_linear_light(img, new_w, new_h, filter);
+    }
+    if filter == ResizeFilter::Lanczos3 {
+        return resample_separable(img, new_w, new_h, &lanczos3, 3.);
+    }
+
+    let (w, h) = img.dimensions();
+    let scale_x = w as f32 / new_w as f32;
+    let scale_y = h as f32 / new_h as f32;
+    ImageBuffer2D::generate(new_w, new_h, |(x, y)| {
+        // Align the pixel centers of both images.
+        let sx = (x as f32 + 0.5) * scale_x - 0.5;
+        let sy = (y as f32 + 0.5) * scale_y - 0.5;
+        sample(img, sx, sy, filter)
+    })
+}
+
+/// Resize an image by independent scale factors along the x and y axes, using the specified interpolation filter. The
+/// output dimensions are the scaled input dimensions rounded to the nearest integer, and are at least one pixel for
+/// non-empty images.
+///
+/// **Panics** if a scale factor is not strictly positive and finite.
+pub fn rescale<P>(img: &dyn Image2D<P>, sx: f32, sy: f32, filter: ResizeFilter) -> ImageBuffer2D<P>
+where
+    P: Pixel,
+{
+    assert!(
+        sx > 0. && sy > 0. && sx.is_finite() && sy.is_finite(),
+        "Invalid scale factors: ({}, {})",
+        sx,
+        sy
+    );
+    let scaled = |len: u32, s: f32| {
+        if len == 0 {
+            0
+        } else {
+            max((len as f32 * s).round() as u32, 1)
+        }
+    };
+    let (w, h) = img.dimensions();
+    resize(img, scaled(w, sx), scaled(h, sy), filter, false)
+}
+
+/// Resize an image to the given dimensions using an arbitrary 1D reconstruction kernel applied separably, such as a
+/// windowed sinc or a spline. `kernel` is evaluated at distances, in source pixels, in `[-support, support]` and is
+/// assumed to be zero outside this interval. As with `ResizeFilter::Lanczos3`, the kernel is stretched when downscaling
+/// to avoid aliasing, and the weights are normalized to sum to one.
+///
+/// **Panics** if the source image is empty and the requested dimensions are not.
+pub fn resize_custom<P, K>(
+    img: &dyn Image2D<P>,
+    new_w: u32,
+    new_h: u32,
+    kernel: K,
+    support: f32,
+) -> ImageBuffer2D<P>
+where
+    P: Pixel,
+    K: Fn(f32) -> f32,
+{
+    resample_separable(img, new_w, new_h, &kernel, support)
+}
+
+// Resize an image in linear light: each channel is decoded into its own floating point plane, which is resized
+// separately before being encoded back.
+fn resize_linear_light<P>(
+    img: &dyn Image2D<P>,
+    new_w: u32,
+    new_h: u32,
+    filter: ResizeFilter,
+) -> ImageBuffer2D<P>
+where
+    P: Pixel,
+{
+    let n_channels = P::N_CHANNELS as usize;
+    let is_alpha = |c: usize| (n_channels == 2 || n_channels == 4) && c == n_channels - 1;
+    let is_integer = <P::Subpixel as NumCast>::from(0.5f32)
+        .unwrap_or_else(P::Subpixel::zero)
+        .is_zero();
+    let scale = if is_integer {
+        <f32 as NumCast>::from(P::Subpixel::max_value()).unwrap()
+    } else {
+        1.
+    };
+
+    let planes = (0..n_channels)
+        .map(|c| {
+            let plane = map_pixels(img, |p| {
+                let v = <f32 as NumCast>::from(p.channels()[c]).unwrap() / scale;
+                Luma::new([if is_alpha(c) { v } else { srgb_decode(v) }])
+            });
+            resize(&plane, new_w, new_h, filter, false)
+        })
+        .collect::<Vec<_>>();
+    let mut channels = Vec::with_capacity(n_channels);
+    ImageBuffer2D::generate(new_w, new_h, |(x, y)| {
+        channels.clear();
+        channels.extend(planes.iter().enumerate().map(|(c, plane)| {
+            let v = plane.get_pixel(x, y).data[0];
+            // Interpolation overshoots may be slightly negative.
+            let v = if is_alpha(c) {
+                v
+            } else {
+                srgb_encode(v.max(0.))
+            };
+            round_saturate::<P::Subpixel>(v * scale)
+        }));
+        P::from_slice(&channels)
+    })
+}
+
+/// Reduce the width of an image by `remove_cols` columns using seam carving: the connected vertical seam of lowest
+/// energy, the energy being the gradient magnitude, is found by dynamic programming and removed, and the process is
+/// repeated for each column. Uniform regions are removed preferentially. `remove_cols` is clamped to the image width.
+pub fn carve_seams(img: &dyn Image2D<Rgb<u8>>, remove_cols: u32) -> ImageBuffer2D<Rgb<u8>> {
+    let (w, h) = img.dimensions();
+    let (mut w, h) = (w as usize, h as usize);
+    let mut pixels = img.iter().cloned().collect::<Vec<Rgb<u8>>>();
+    for _ in 0..min(remove_cols as usize, w) {
+        let seam = find_seam(&pixels, w, h);
+        let mut carved = Vec::with_capacity((w - 1) * h);
+        for (row, x) in pixels.chunks(w).zip(seam) {
+            carved.extend_from_slice(&row[..x]);
+            carved.extend_from_slice(&row[x + 1..]);
+        }
+        pixels = carved;
+        w -= 1;
+    }
+    ImageBuffer2D::from_vec(w as u32, h as u32, pixels).unwrap()
+}
+
+// Find the vertical seam of lowest energy in a `w x h` buffer, returned as the column index of the seam in each row.
+fn find_seam(pixels: &[Rgb<u8>], w: usize, h: usize) -> Vec<usize> {
+    let channel = |x: usize, y: usize, c: usize| pixels[y * w + x].data[c] as f32;
+    let energy = |x: usize, y: usize| {
+        let (x0, x1) = (x.saturating_sub(1), min(x + 1, w - 1));
+        let (y0, y1) = (y.saturating_sub(1), min(y + 1, h - 1));
+        (0..3)
+            .map(|c| {
+                let dx = channel(x1, y, c) - channel(x0, y, c);
+                let dy = channel(x, y1, c) - channel(x, y0, c);
+                dx * dx + dy * dy
+            })
+            .sum::<f32>()
+            .sqrt()
+    };
+
+    // Cumulative minimal energy of the seams ending at each pixel.
+    let mut cost = vec![0f32; w * h];
+    for y in 0..h {
+        for x in 0..w {
+            let above = if y == 0 {
+                0.
+            } else {
+                (x.saturating_sub(1)..min(x + 2, w))
+                    .map(|px| cost[(y - 1) * w + px])
+                    .fold(f32::INFINITY, f32::min)
+            };
+            cost[y * w + x] = above + energy(x, y);
+        }
+    }
+
+    let argmin = |y: usize, range: ::std::ops::Range<usize>| {
+        range
+            .min_by(|&a, &b| cost[y * w + a].partial_cmp(&cost[y * w + b]).unwrap())
+            .unwrap()
+    };
+    let mut seam = vec![0; h];
+    if h > 0 {
+        seam[h - 1] = argmin(h - 1, 0..w);
+    }
+    for y in (0..h.saturating_sub(1)).rev() {
+        let x = seam[y + 1];
+        seam[y] = argmin(y, x.saturating_sub(1)..min(x + 2, w));
+    }
+    seam
+}
+
+// Compute the normalized weights of the source pixels contributing to each destination pixel along one axis. The
+// kernel is stretched when downscaling so that it acts as a low-pass filter.
+fn axis_weights<K>(src_len: u32, dst_len: u32, kernel: &K, support: f32) -> Vec<Vec<(u32, f32)>>
+where
+    K: Fn(f32) -> f32,
+{
+    let scale = src_len as f32 / dst_len as f32;
+    let filter_scale = scale.max(1.);
+    let radius = support * filter_scale;
+    (0..dst_len)
+        .map(|i| {
+            let center = (i as f32 + 0.5) * scale - 0.5;
+            let first = (center - radius).ceil() as i64;
+            let last = (center + radius).floor() as i64;
+            let mut weights = (first..=last)
+                .map(|j| {
+                    let src = min(max(j, 0), <i64 as From<u32>>::from(src_len) - 1) as u32;
+                    (src, kernel((j as f32 - center) / filter_scale))
+                })
+                .collect::<Vec<_>>();
+            let sum = weights.iter().fold(0., |acc, &(_, w)| acc + w);
+            if sum != 0. {
+                for w in &mut weights {
+                    w.1 /= sum;
+                }
+            }
+            weights
+        })
+        .collect()
+}
+
+// Resample an image by applying a 1D reconstruction kernel horizontally then vertically.
+fn resample_separable<P, K>(
+    img: &dyn Image2D<P>,
+    new_w: u32,
+    new_h: u32,
+    kernel: &K,
+    support: f32,
+) -> ImageBuffer2D<P>
+where
+    P: Pixel,
+    K: Fn(f32) -> f32,
+{
+    let (w, h) = img.dimensions();
+    let n_channels = P::N_CHANNELS as usize;
+    let weights_x = axis_weights(w, new_w, kernel, support);
+    let weights_y = axis_weights(h, new_h, kernel, support);
+
+    // Horizontal pass, storing the intermediate result as floating point values.
+    let mut tmp = vec![0f32; new_w as usize * h as usize * n_channels];
+    for y in 0..h {
+        for (x, weights) in weights_x.iter().enumerate() {
+            let offset = (y as usize * new_w as usize + x) * n_channels;
+            let accu = &mut tmp[offset..offset + n_channels];
+            for &(sx, wx) in weights {
+                for (a, c) in accu.iter_mut().zip(img.get_pixel(sx, y).channels()) {
+                    *a += wx * <f32 as NumCast>::from(*c).unwrap();
+                }
+            }
+        }
+    }
+
+    // Vertical pass.
+    let mut accu = vec![0f32; n_channels];
+    let mut channels = Vec::with_capacity(n_channels);
+    ImageBuffer2D::generate(new_w, new_h, |(x, y)| {
+        accu.iter_mut().for_each(|a| *a = 0.);
+        for &(sy, wy) in &weights_y[y as usize] {
+            let offset = (sy as usize * new_w as usize + x as usize) * n_channels;
+            for (a, c) in accu.iter_mut().zip(&tmp[offset..offset + n_channels]) {
+                *a += wy * c;
+            }
+        }
+        channels.clear();
+        channels.extend(accu.iter().map(|a| round_saturate::<P::Subpixel>(*a)));
+        P::from_slice(&channels)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::RgbA;
+
+    #[test]
+    fn test_resize_nearest() {
+        let img = ImageBuffer2D::generate(2, 2, |(x, y)| Luma::new([(x + 2 * y) as u8]));
+        let resized = resize(&img, 4, 4, ResizeFilter::Nearest, false);
+        assert_eq!(resized.dimensions(), (4, 4));
+        for y in 0..4 {
+            for x in 0..4 {
+                assert_eq!(resized.get_pixel(x, y), img.get_pixel(x / 2, y / 2));
+            }
+        }
+    }
+
+    #[test]
+    fn test_bicubic_interpolates_samples() {
+        let img =
+            ImageBuffer2D::generate(8, 8, |(x, y)| Luma::new([((x * 37 + y * 11) % 256) as u8]));
+        for y in 0..8 {
+            for x in 0..8 {
+                let s = sample(&img, x as f32, y as f32, ResizeFilter::Bicubic);
+                assert_eq!(&s, img.get_pixel(x, y));
+            }
+        }
+        assert_eq!(resize(&img, 8, 8, ResizeFilter::Bicubic, false), img);
+    }
+
+    #[test]
+    fn test_bicubic_ramp_monotonic() {
+        let img = ImageBuffer2D::generate(16, 4, |(x, _y)| Luma::new([x as f32 * 10.]));
+        let resized = resize(&img, 64, 4, ResizeFilter::Bicubic, false);
+        for row in resized.rows() {
+            let row = row.into_iter().map(|p| p.data[0]).collect::<Vec<f32>>();
+            for pair in row.windows(2) {
+                assert!(pair[1] >= pair[0] - 1e-4, "{:?}", pair);
+            }
+        }
+    }
+
+    #[test]
+    fn test_line_profile() {
+        let img = ImageBuffer2D::generate(16, 8, |(x, _y)| Luma::new([x as f32 * 10.]));
+        let profile = line_profile(&img, (-2., 3.5), (20., 3.5), 50);
+        assert_eq!(profile.len(), 50);
+        assert_eq!(profile[0], Luma::new([0.]));
+        assert_eq!(profile[49], Luma::new([150.]));
+        for pair in profile.windows(2) {
+            assert!(pair[1].data[0] >= pair[0].data[0]);
+        }
+        assert!(profile[10].data[0] < profile[40].data[0]);
+
+        let profile = line_profile(&img, (1.5, 0.), (1.5, 7.), 4);
+        assert!(profile.iter().all(|p| p == &Luma::new([15.])));
+        assert!(line_profile(&img, (0., 0.), (1., 1.), 0).is_empty());
+    }
+
+    #[test]
+    fn test_carve_seams() {
+        let texture = |x: u32, y: u32| {
+            let v = ((x * 37 + y * 91) % 256) as u8;
+            Rgb::new([v, 255 - v, v / 2])
+        };
+        let stripe = Rgb::new([90u8, 120, 30]);
+        let img = ImageBuffer2D::generate(20, 10, |(x, y)| {
+            if (7..13).contains(&x) {
+                stripe
+            } else {
+                texture(x, y)
+            }
+        });
+        let carved = carve_seams(&img, 3);
+        assert_eq!(carved.dimensions(), (17, 10));
+        for ((x, y), p) in carved
+            .enumerate_pixels()
+            .map(|((y, x), p)| ((x as u32, y as u32), p))
+        {
+            let expected = match x {
+                0..=6 => texture(x, y),
+                7..=9 => stripe,
+                _ => texture(x + 3, y),
+            };
+            assert_eq!(p, &expected);
+        }
+        assert_eq!(carve_seams(&img, 30).dimensions(), (0, 10));
+    }
+
+    #[test]
+    fn test_lanczos_constant() {
+        let img = ImageBuffer2D::generate(37, 23, |_| Luma::new([173u8]));
+        for &(w, h) in &[(10, 7), (37, 23), (80, 50)] {
+            let resized = resize(&img, w, h, ResizeFilter::Lanczos3, false);
+            assert_eq!(resized.dimensions(), (w, h));
+            assert!(resized.iter().all(|p| p == &Luma::new([173u8])));
+        }
+    }
+
+    #[test]
+    fn test_lanczos_antialiasing() {
+        fn variance(img: &ImageBuffer2D<Luma<f32>>) -> f32 {
+            let n = (img.width() * img.height()) as f32;
+            let mean = img.iter().fold(0., |acc, p| acc + p.data[0]) / n;
+            img.iter()
+                .fold(0., |acc, p| acc + (p.data[0] - mean) * (p.data[0] - mean))
+                / n
+        }
+
+        // Frequency well above the Nyquist frequency of the downscaled image.
+        let img = ImageBuffer2D::generate(256, 8, |(x, _y)| {
+            Luma::new([128. + 127. * (2. * PI * 0.45 * x as f32).sin()])
+        });
+        let nearest = resize(&img, 64, 2, ResizeFilter::Nearest, false);
+        let lanczos = resize(&img, 64, 2, ResizeFilter::Lanczos3, false);
+        assert!(variance(&lanczos) * 10. < variance(&nearest));
+    }
+
+    #[test]
+    fn test_resize_gamma_correct() {
+        let stripes = ImageBuffer2D::generate(16, 4, |(x, _y)| Luma::new([255 * (x % 2) as u8]));
+        let naive = resize(&stripes, 8, 2, ResizeFilter::Bilinear, false);
+        let linear = resize(&stripes, 8, 2, ResizeFilter::Bilinear, true);
+        assert!(naive.iter().all(|p| p.data[0] == 128), "{:?}", naive);
+        assert!(linear.iter().all(|p| p.data[0] == 188), "{:?}", linear);
+
+        // Alpha is averaged without gamma correction, and uniform images are preserved.
+        let img = ImageBuffer2D::generate(16, 4, |(x, _y)| {
+            RgbA::new([255 * (x % 2) as u8, 40, 200, 255 * (x % 2) as u8])
+        });
+        let resized = resize(&img, 8, 2, ResizeFilter::Bilinear, true);
+        assert!(resized.iter().all(|p| p == &RgbA::new([188, 40, 200, 128])));
+    }
+
+    #[test]
+    fn test_resize_custom() {
+        let img = ImageBuffer2D::generate(7, 5, |(x, y)| {
+            Rgb::new([
+                (x * 40) as u8,
+                (y * y * 10) as u8,
+                ((x * y) % 3 * 100) as u8,
+            ])
+        });
+        let triangle = |t: f32| (1. - t.abs()).max(0.);
+        for &(w, h) in &[(7, 5), (20, 13), (14, 5), (9, 31)] {
+            assert_eq!(
+                resize_custom(&img, w, h, triangle, 1.),
+                resize(&img, w, h, ResizeFilter::Bilinear, false)
+            );
+        }
+        assert_eq!(
+            resize_custom(&img, 12, 8, lanczos3, 3.),
+            resize(&img, 12, 8, ResizeFilter::Lanczos3, false)
+        );
+        // A box kernel averages the source pixels when downscaling by an integer factor.
+        let img = ImageBuffer2D::generate(4, 2, |(x, y)| Luma::new([(x * 10 + y * 20) as u8]));
+        let box_kernel = |t: f32| if t.abs() < 0.5 { 1. } else { 0. };
+        let resized = resize_custom(&img, 2, 1, box_kernel, 0.5);
+        assert_eq!(resized.get_pixel(0, 0), &Luma::new([15]));
+        assert_eq!(resized.get_pixel(1, 0), &Luma::new([35]));
+    }
+
+    #[test]
+    fn test_rescale() {
+        let img = ImageBuffer2D::generate(10, 8, |(x, y)| Luma::new([(x * 20 + y) as u8]));
+        let scaled = rescale(&img, 2., 0.5, ResizeFilter::Nearest);
+        assert_eq!(scaled.dimensions(), (20, 4));
+        assert_eq!(scaled, resize(&img, 20, 4, ResizeFilter::Nearest, false));
+        assert_eq!(
+            rescale(&img, 0.35, 1.5, ResizeFilter::Bilinear).dimensions(),
+            (4, 12)
+        );
+        assert_eq!(
+            rescale(&img, 0.01, 0.01, ResizeFilter::Bilinear).dimensions(),
+            (1, 1)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_rescale_invalid_factor() {
+        let img = ImageBuffer2D::<Luma<u8>>::new(10, 8);
+        rescale(&img, 1., -1., ResizeFilter::Nearest);
+    }
+}
@@ -0,0 +1,331 @@
+//! Contains image segmentation algorithms.
+
+use core::{Image2D, ImageBuffer2D, Luma, Rgb};
+use helper::generic::round_saturate;
+use processing::color::rgb_to_lab;
+
+use std::cmp::{max, min};
+
+/// Segment an image with the mean shift algorithm: starting from each pixel, the mean of the pixels within
+/// `spatial_radius` pixels along each axis and within a color distance of `color_radius` is computed in the joint
+/// spatial and color space, and the window is moved to that mean until it moves by less than half a pixel and half a
+/// color level, or until `max_iter` iterations. Each pixel is replaced by the color of the mode it converges to, so
+/// homogeneous regions collapse to a single color while edges are preserved.
+pub fn mean_shift_segment(
+    img: &dyn Image2D<Rgb<u8>>,
+    spatial_radius: u32,
+    color_radius: f32,
+    max_iter: u32,
+) -> ImageBuffer2D<Rgb<u8>> {
+    let (w, h) = img.dimensions();
+    let color = |x: u32, y: u32| {
+        let p = img.get_pixel(x, y);
+        [
+            f32::from(p.data[0]),
+            f32::from(p.data[1]),
+            f32::from(p.data[2]),
+        ]
+    };
+    let r = spatial_radius as f32;
+    let cr2 = color_radius * color_radius;
+    ImageBuffer2D::generate(w, h, |(x, y)| {
+        let (mut px, mut py, mut c) = (x as f32, y as f32, color(x, y));
+        for _ in 0..max_iter {
+            let (x0, x1) = (
+                (px - r).ceil().max(0.) as u32,
+                min((px + r).floor() as u32, w - 1),
+            );
+            let (y0, y1) = (
+                (py - r).ceil().max(0.) as u32,
+                min((py + r).floor() as u32, h - 1),
+            );
+            let (mut sx, mut sy, mut sc, mut n) = (0., 0., [0f32; 3], 0.);
+            for wy in y0..=y1 {
+                for wx in x0..=x1 {
+                    let q = color(wx, wy);
+                    let d2 = (0..3).map(|i| (q[i] - c[i]) * (q[i] - c[i])).sum::<f32>();
+                    if d2 <= cr2 {
+                        sx += wx as f32;
+                        sy += wy as f32;
+                        for (s, v) in sc.iter_mut().zip(&q) {
+                            *s += v;
+                        }
+                        n += 1.;
+                    }
+                }
+            }
+            if n == 0. {
+                break;
+            }
+            let (nx, ny) = (sx / n, sy / n);
+            let nc = [sc[0] / n, sc[1] / n, sc[2] / n];
+            let shift = (nx - px) * (nx - px) + (ny - py) * (ny - py);
+            let color_shift = (0..3).map(|i| (nc[i] - c[i]) * (nc[i] - c[i])).sum::<f32>();
+            px = nx;
+            py = ny;
+            c = nc;
+            if shift < 0.25 && color_shift < 0.25 {
+                break;
+            }
+        }
+        Rgb::new([
+            round_saturate(c[0]),
+            round_saturate(c[1]),
+            round_saturate(c[2]),
+        ])
+    })
+}
+
+// Number of assignment and update iterations performed by SLIC.
+const SLIC_ITERATIONS: u32 = 10;
+
+/// Partition an image into about `n_superpixels` compact regions of similar colors with the SLIC algorithm, working in
+/// the CIE L\*a\*b\* color space. Cluster centers are initialized on a regular grid, and pixels are assigned to the
+/// closest center within a window of twice the grid step, the distance combining the color distance with the spatial
+/// distance weighted by `compactness`. Typical values of `compactness` are in `[1, 40]`, larger values giving more
+/// regular superpixels. Finally, disconnected fragments are merged into an adjacent superpixel, so that every label
+/// forms a 4-connected region.
+///
+/// Returns an image of labels, numbered consecutively from 0 in scanline order of their first pixel.
+///
+/// **Panics** if `n_superpixels` is zero.
+pub fn slic(
+    img: &dyn Image2D<Rgb<u8>>,
+    n_superpixels: u32,
+    compactness: f32,
+) -> ImageBuffer2D<Luma<u32>> {
+    assert!(
+        n_superpixels > 0,
+        "The number of superpixels must be strictly positive."
+    );
+    let (w, h) = (img.width() as usize, img.height() as usize);
+    if w == 0 || h == 0 {
+        return ImageBuffer2D::new(img.width(), img.height());
+    }
+    let lab = rgb_to_lab(img)
+        .iter()
+        .map(|p| p.data)
+        .collect::<Vec<[f32; 3]>>();
+    let step = ((w * h) as f32 / n_superpixels as f32).sqrt().max(1.);
+
+    // Cluster centers, as [x, y, L, a, b].
+    let mut centers = Vec::new();
+    let (nx, ny) = (
+        max((w as f32 / step).round() as usize, 1),
+        max((h as f32 / step).round() as usize, 1),
+    );
+    for j in 0..ny {
+        for i in 0..nx {
+            let x = ((i as f32 + 0.5) * w as f32 / nx as f32) as usize;
+            let y = ((j as f32 + 0.5) * h as f32 / ny as f32) as usize;
+            let c = lab[y * w + x];
+            centers.push([x as f32, y as f32, c[0], c[1], c[2]]);
+        }
+    }
+
+    let mut labels = vec![0usize; w * h];
+    let mut distances = vec![0f32; w * h];
+    let weight = (compactness / step) * (compactness / step);
+    for _ in 0..SLIC_ITERATIONS {
+        distances.iter_mut().for_each(|d| *d = f32::INFINITY);
+        for (k, center) in centers.iter().enumerate() {
+            let (x0, x1) = (
+                (center[0] - step).max(0.) as usize,
+                min((center[0] + step) as usize + 1, w),
+            );
+            let (y0, y1) = (
+                (center[1] - step).max(0.) as usize,
+                min((center[1] + step) as usize + 1, h),
+            );
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    let c = lab[y * w + x];
+                    let dc = (0..3)
+                        .map(|i| (c[i] - center[i + 2]) * (c[i] - center[i + 2]))
+                        .sum::<f32>();
+                    let (dx, dy) = (x as f32 - center[0], y as f32 - center[1]);
+                    let d = dc + (dx * dx + dy * dy) * weight;
+                    if d < distances[y * w + x] {
+                        distances[y * w + x] = d;
+                        labels[y * w + x] = k;
+                    }
+                }
+            }
+        }
+
+        let mut sums = vec![[0f32; 6]; centers.len()];
+        for (i, &k) in labels.iter().enumerate() {
+            let c = lab[i];
+            let values = [(i % w) as f32, (i / w) as f32, c[0], c[1], c[2], 1.];
+            for (s, v) in sums[k].iter_mut().zip(&values) {
+                *s += v;
+            }
+        }
+        for (center, sum) in centers.iter_mut().zip(&sums) {
+            if sum[5] > 0. {
+                for (c, s) in center.iter_mut().zip(sum) {
+                    *c = s / sum[5];
+                }
+            }
+        }
+    }
+
+    let labels = enforce_connectivity(&labels, w, h, (step * step / 4.) as usize);
+    let labels = labels.into_iter().map(|l| l as u32).collect::<Vec<u32>>();
+    ImageBuffer2D::from_raw_vec(w as u32, h as u32, &labels).unwrap()
+}
+
+// Relabel the 4-connected components of a `w x h` label buffer consecutively, merging the components smaller than
+// `min_size` pixels into the component on their left or above their first pixel.
+fn enforce_connectivity(labels: &[usize], w: usize, h: usize, min_size: usize) -> Vec<usize> {
+    const UNSET: usize = usize::MAX;
+    let mut out = vec![UNSET; w * h];
+    let mut component = Vec::new();
+    let mut next_label = 0;
+    for start in 0..w * h {
+        if out[start] != UNSET {
+            continue;
+        }
+        // Label of a previously visited neighbor, into which a small component is merged.
+        let adjacent = if start % w > 0 {
+            Some(out[start - 1])
+        } else if start >= w {
+            Some(out[start - w])
+        } else {
+            None
+        };
+
+        component.clear();
+        component.push(start);
+        out[start] = next_label;
+        let mut i = 0;
+        while i < component.len() {
+            let p = component[i];
+            let (x, y) = (p % w, p / w);
+            let mut neighbors = [None; 4];
+            if x > 0 {
+                neighbors[0] = Some(p - 1);
+            }
+            if x + 1 < w {
+                neighbors[1] = Some(p + 1);
+            }
+            if y > 0 {
+                neighbors[2] = Some(p - w);
+            }
+            if y + 1 < h {
+                neighbors[3] = Some(p + w);
+            }
+            for q in neighbors.iter().filter_map(|&q| q) {
+                if out[q] == UNSET && labels[q] == labels[start] {
+                    out[q] = next_label;
+                    component.push(q);
+                }
+            }
+            i += 1;
+        }
+
+        match adjacent {
+            Some(label) if component.len() < min_size => {
+                for &p in &component {
+                    out[p] = label;
+                }
+            }
+            _ => next_label += 1,
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_mean_shift_segment() {
+        let colors = [[200, 40, 40], [40, 180, 60], [30, 50, 220]];
+        let region = |x: u32, y: u32| {
+            if x < 10 {
+                0
+            } else if y < 10 {
+                1
+            } else {
+                2
+            }
+        };
+        let flat = ImageBuffer2D::generate(30, 20, |(x, y)| Rgb::new(colors[region(x, y)]));
+        let segmented = mean_shift_segment(&flat, 4, 30., 20);
+        let distinct = segmented.iter().map(|p| p.data).collect::<HashSet<_>>();
+        assert_eq!(distinct.len(), 3);
+        assert_eq!(segmented, flat);
+
+        // With a small noise, of mean 3, each region collapses close to its mean color.
+        let noisy = ImageBuffer2D::generate(30, 20, |(x, y)| {
+            let noise = ((x * 7 + y * 13) % 7) as u8;
+            let c = colors[region(x, y)];
+            Rgb::new([c[0] + noise, c[1] + noise, c[2] - noise])
+        });
+        let segmented = mean_shift_segment(&noisy, 4, 30., 20);
+        for ((y, x), p) in segmented.enumerate_pixels() {
+            let c = colors[region(x as u32, y as u32)];
+            let expected = [c[0] + 3, c[1] + 3, c[2] - 3];
+            for (v, e) in p.data.iter().zip(&expected) {
+                assert!((i32::from(*v) - i32::from(*e)).abs() <= 2, "{:?}", p);
+            }
+        }
+    }
+
+    // Count the 4-connected components of each label.
+    fn components_per_label(labels: &ImageBuffer2D<Luma<u32>>) -> Vec<usize> {
+        let (w, h) = labels.dimensions();
+        let n = labels.iter().map(|p| p.data[0]).max().unwrap() as usize + 1;
+        let mut counts = vec![0; n];
+        let mut visited = vec![false; (w * h) as usize];
+        for start in 0..(w * h) as usize {
+            if visited[start] {
+                continue;
+            }
+            let label = labels.get_pixel(start as u32 % w, start as u32 / w).data[0];
+            counts[label as usize] += 1;
+            let mut stack = vec![start];
+            visited[start] = true;
+            while let Some(p) = stack.pop() {
+                let (x, y) = ((p as u32 % w) as i64, (p as u32 / w) as i64);
+                for &(dx, dy) in &[(-1, 0), (1, 0), (0, -1), (0, 1)] {
+                    let (nx, ny) = (x + dx, y + dy);
+                    if nx < 0 || ny < 0 || nx >= i64::from(w) || ny >= i64::from(h) {
+                        continue;
+                    }
+                    let q = (ny * i64::from(w) + nx) as usize;
+                    if !visited[q] && labels.get_pixel(nx as u32, ny as u32).data[0] == label {
+                        visited[q] = true;
+                        stack.push(q);
+                    }
+                }
+            }
+        }
+        counts
+    }
+
+    #[test]
+    fn test_slic() {
+        let img = ImageBuffer2D::generate(60, 40, |(x, y)| {
+            let inside = (x as i32 - 35).pow(2) + (y as i32 - 18).pow(2) < 150;
+            if inside {
+                Rgb::new([220, 60, 50])
+            } else {
+                Rgb::new([(x * 3) as u8, (100 + y * 2) as u8, 120])
+            }
+        });
+        let labels = slic(&img, 24, 10.);
+        assert_eq!(labels.dimensions(), (60, 40));
+        let counts = components_per_label(&labels);
+        assert!(counts.len() >= 16 && counts.len() <= 32, "{}", counts.len());
+        assert!(counts.iter().all(|&c| c == 1), "{:?}", counts);
+
+        let uniform = ImageBuffer2D::generate(20, 20, |_| Rgb::new([80u8, 80, 80]));
+        let labels = slic(&uniform, 4, 10.);
+        assert_eq!(components_per_label(&labels), vec![1; 4]);
+    }
+}
@@ -0,0 +1,466 @@
+//! Contains shape analysis operations on binary images, where non-zero pixels are considered to be part of the
+//! foreground.
+
+use core::{Image2D, Luma};
+
+use std::f64::consts::FRAC_PI_2;
+
+/// Raw spatial moments of a binary region, up to the second order. The coordinates of a pixel are those of its center,
+/// i.e. its integer indices.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Moments {
+    /// Area of the region, i.e. its number of pixels.
+    pub m00: f64,
+    /// Sum of the x coordinates.
+    pub m10: f64,
+    /// Sum of the y coordinates.
+    pub m01: f64,
+    /// Sum of the products of the x and y coordinates.
+    pub m11: f64,
+    /// Sum of the squared x coordinates.
+    pub m20: f64,
+    /// Sum of the squared y coordinates.
+    pub m02: f64,
+}
+
+impl Moments {
+    /// Return the centroid `(x, y)` of the region, or `None` if the region is empty.
+    pub fn centroid(&self) -> Option<(f64, f64)> {
+        if self.m00 == 0. {
+            None
+        } else {
+            Some((self.m10 / self.m00, self.m01 / self.m00))
+        }
+    }
+
+    // Return the normalized central moments (mu20, mu02, mu11) of the region, or `None` if the region is empty.
+    fn central(&self) -> Option<(f64, f64, f64)> {
+        self.centroid().map(|(cx, cy)| {
+            (
+                self.m20 / self.m00 - cx * cx,
+                self.m02 / self.m00 - cy * cy,
+                self.m11 / self.m00 - cx * cy,
+            )
+        })
+    }
+
+    /// Return the angle in radians between the x axis and the principal axis of the region, in `[-pi/2, pi/2]`. The
+    /// y axis points down, so positive angles are clockwise on screen. Empty regions have an orientation of zero.
+    pub fn orientation(&self) -> f32 {
+        match self.central() {
+            Some((mu20, mu02, mu11)) => (0.5 * (2. * mu11).atan2(mu20 - mu02)) as f32,
+            None => 0.,
+        }
+    }
+
+    /// Return the eccentricity of the ellipse having the same second moments as the region, from 0 for a circle to
+    /// close to 1 for a line. Empty regions have an eccentricity of zero.
+    pub fn eccentricity(&self) -> f32 {
+        match self.central() {
+            Some((mu20, mu02, mu11)) => {
+                let mean = (mu20 + mu02) / 2.;
+                let delta = (mu11 * mu11 + (mu20 - mu02) * (mu20 - mu02) / 4.).sqrt();
+                let (major, minor) = (mean + delta, mean - delta);
+                if major > 0. {
+                    (1. - minor / major).max(0.).sqrt() as f32
+                } else {
+                    0.
+                }
+            }
+            None => 0.,
+        }
+    }
+}
+
+/// Compute the raw moments of the region made of the non-zero pixels of an image.
+pub fn region_moments(img: &dyn Image2D<Luma<u8>>) -> Moments {
+    let mut m = Moments::default();
+    for ((y, x), p) in img.enumerate_pixels() {
+        if p.data[0] != 0 {
+            let (x, y) = (x as f64, y as f64);
+            m.m00 += 1.;
+            m.m10 += x;
+            m.m01 += y;
+            m.m11 += x * y;
+            m.m20 += x * x;
+            m.m02 += y * y;
+        }
+    }
+    m
+}
+
+// Offsets to the 8 neighbors of a pixel, in clockwise order starting from the west.
+const NEIGHBORS: [(i64, i64); 8] = [
+    (-1, 0),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+];
+
+/// Trace the outer boundary of each 8-connected foreground component of an image using Moore-neighbor tracing.
+/// Each contour is a closed chain of pixel coordinates in clockwise order, starting from the top-left pixel of the
+/// component and not repeating it at the end. Components are returned in the raster order of their top-left pixels.
+pub fn find_contours(img: &dyn Image2D<Luma<u8>>) -> Vec<Vec<(u32, u32)>> {
+    let (w, h) = (i64::from(img.width()), i64::from(img.height()));
+    let fg = |x: i64, y: i64| {
+        x >= 0 && y >= 0 && x < w && y < h && img.get_pixel(x as u32, y as u32).data[0] != 0
+    };
+    let mut visited = vec![false; (w * h) as usize];
+    let mut contours = Vec::new();
+    let mut stack = Vec::new();
+    for y in 0..h {
+        for x in 0..w {
+            if visited[(y * w + x) as usize] || !fg(x, y) {
+                continue;
+            }
+            // Mark the whole component so that it is traced only once.
+            visited[(y * w + x) as usize] = true;
+            stack.push((x, y));
+            while let Some((cx, cy)) = stack.pop() {
+                for &(dx, dy) in &NEIGHBORS {
+                    let (nx, ny) = (cx + dx, cy + dy);
+                    if fg(nx, ny) && !visited[(ny * w + nx) as usize] {
+                        visited[(ny * w + nx) as usize] = true;
+                        stack.push((nx, ny));
+                    }
+                }
+            }
+            contours.push(trace_boundary(&fg, (x, y)));
+        }
+    }
+    contours
+}
+
+// Follow the boundary of the component containing `start`, which must be its top-left pixel so that its western
+// neighbor is background. Tracing stops when the second pixel of the contour is about to be visited again from
+// the start pixel, so pixels on one pixel wide parts of the component appear several times.
+fn trace_boundary<F>(fg: &F, start: (i64, i64)) -> Vec<(u32, u32)>
+where
+    F: Fn(i64, i64) -> bool,
+{
+    let mut contour = vec![start];
+    let (mut current, mut backtrack) = (start, 0);
+    loop {
+        let next = (1..9)
+            .map(|k| (backtrack + k) % 8)
+            .find(|&d| fg(current.0 + NEIGHBORS[d].0, current.1 + NEIGHBORS[d].1));
+        let d = match next {
+            Some(d) => d,
+            // Isolated pixel.
+            None => break,
+        };
+        let pixel = (current.0 + NEIGHBORS[d].0, current.1 + NEIGHBORS[d].1);
+        if current == start && contour.len() > 1 && pixel == contour[1] {
+            contour.pop();
+            break;
+        }
+        // The last background pixel examined, expressed as a direction from the new pixel.
+        let (bx, by) = NEIGHBORS[(d + 7) % 8];
+        let back = (current.0 + bx - pixel.0, current.1 + by - pixel.1);
+        backtrack = NEIGHBORS.iter().position(|&n| n == back).unwrap();
+        contour.push(pixel);
+        current = pixel;
+    }
+    contour
+        .into_iter()
+        .map(|(x, y)| (x as u32, y as u32))
+        .collect()
+}
+
+/// Simplify a closed contour with the Ramer-Douglas-Peucker algorithm, keeping only the vertices needed for every
+/// point of the contour to be within `epsilon` pixels of the resulting polygon. The contour is split at its first
+/// point and at the point farthest from it, and each half is simplified separately. The vertices are returned in
+/// the order of the contour.
+pub fn approx_poly_dp(contour: &[(u32, u32)], epsilon: f32) -> Vec<(u32, u32)> {
+    if contour.len() < 3 {
+        return contour.to_vec();
+    }
+    let dist2 = |a: (u32, u32), b: (u32, u32)| {
+        let (dx, dy) = (a.0 as f32 - b.0 as f32, a.1 as f32 - b.1 as f32);
+        dx * dx + dy * dy
+    };
+    let far = (1..contour.len())
+        .max_by(|&i, &j| {
+            dist2(contour[0], contour[i])
+                .partial_cmp(&dist2(contour[0], contour[j]))
+                .unwrap()
+        })
+        .unwrap();
+    let mut closed = contour.to_vec();
+    closed.push(contour[0]);
+
+    let mut keep = vec![false; closed.len()];
+    keep[0] = true;
+    keep[far] = true;
+    simplify(&closed, 0, far, epsilon, &mut keep);
+    simplify(&closed, far, closed.len() - 1, epsilon, &mut keep);
+    contour
+        .iter()
+        .zip(&keep)
+        .filter(|&(_, &k)| k)
+        .map(|(p, _)| *p)
+        .collect()
+}
+
+// Mark the vertices to keep between `first` and `last`, which are both kept.
+fn simplify(points: &[(u32, u32)], first: usize, last: usize, epsilon: f32, keep: &mut [bool]) {
+    if last <= first + 1 {
+        return;
+    }
+    let (x0, y0) = (points[first].0 as f32, points[first].1 as f32);
+    let (x1, y1) = (points[last].0 as f32, points[last].1 as f32);
+    let len = ((x1 - x0) * (x1 - x0) + (y1 - y0) * (y1 - y0)).sqrt();
+    let distance = |&(x, y): &(u32, u32)| {
+        let (x, y) = (x as f32, y as f32);
+        if len == 0. {
+            ((x - x0) * (x - x0) + (y - y0) * (y - y0)).sqrt()
+        } else {
+            ((x1 - x0) * (y0 - y) - (x0 - x) * (y1 - y0)).abs() / len
+        }
+    };
+    let (index, max) = points[first + 1..last]
+        .iter()
+        .map(distance)
+        .enumerate()
+        .fold(
+            (0, 0.),
+            |(i, m), (j, d)| if d > m { (j, d) } else { (i, m) },
+        );
+    if max > epsilon {
+        let index = first + 1 + index;
+        keep[index] = true;
+        simplify(points, first, index, epsilon, keep);
+        simplify(points, index, last, epsilon, keep);
+    }
+}
+
+// Cross product of `a - o` and `b - o`, positive when `o, a, b` turn counter-clockwise.
+fn cross(o: (i64, i64), a: (i64, i64), b: (i64, i64)) -> i64 {
+    (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+}
+
+/// Compute the convex hull of a set of points with Andrew's monotone chain algorithm. The vertices are returned in
+/// counter-clockwise order for a y axis pointing up (i.e. clockwise on screen), starting from the point with the
+/// smallest coordinates, without collinear points or a repeated endpoint.
+pub fn convex_hull(points: &[(i64, i64)]) -> Vec<(i64, i64)> {
+    let mut sorted = points.to_vec();
+    sorted.sort();
+    sorted.dedup();
+    if sorted.len() < 3 {
+        return sorted;
+    }
+    let mut hull: Vec<(i64, i64)> = Vec::with_capacity(2 * sorted.len());
+    // Build the lower chain, then the upper one, popping the vertices which do not make a left turn.
+    for pass in 0..2 {
+        let start = hull.len();
+        for &p in sorted.iter() {
+            while hull.len() >= start + 2
+                && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0
+            {
+                hull.pop();
+            }
+            hull.push(p);
+        }
+        // The last point of each chain is the first point of the other one.
+        hull.pop();
+        if pass == 0 {
+            sorted.reverse();
+        }
+    }
+    hull
+}
+
+/// A rectangle of arbitrary orientation.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct RotatedRect {
+    /// Coordinates `(x, y)` of the center of the rectangle.
+    pub center: (f32, f32),
+    /// Dimensions `(width, height)` of the rectangle, the width being measured along the direction given by `angle`.
+    pub size: (f32, f32),
+    /// Angle in radians between the x axis and the width side of the rectangle, in `[0, pi/2)`.
+    pub angle: f32,
+}
+
+/// Compute the rectangle of minimum area enclosing a set of points. Such a rectangle has a side collinear with an
+/// edge of the convex hull of the points, so each edge of the hull is tried in turn as the caliper direction.
+/// Returns a default rectangle for an empty set of points.
+pub fn min_area_rect(points: &[(i64, i64)]) -> RotatedRect {
+    let hull = convex_hull(points);
+    if hull.len() == 1 {
+        return RotatedRect {
+            center: (hull[0].0 as f32, hull[0].1 as f32),
+            ..RotatedRect::default()
+        };
+    }
+    let (mut best, mut best_area) = (RotatedRect::default(), f64::INFINITY);
+    for (i, &(x0, y0)) in hull.iter().enumerate() {
+        let (x1, y1) = hull[(i + 1) % hull.len()];
+        let angle = ((y1 - y0) as f64)
+            .atan2((x1 - x0) as f64)
+            .rem_euclid(FRAC_PI_2);
+        let (sin, cos) = angle.sin_cos();
+        // Bounds of the hull in the frame rotated by `angle`.
+        let (mut umin, mut umax, mut vmin, mut vmax) = (f64::MAX, f64::MIN, f64::MAX, f64::MIN);
+        for &(x, y) in &hull {
+            let (x, y) = (x as f64, y as f64);
+            let (u, v) = (x * cos + y * sin, y * cos - x * sin);
+            umin = umin.min(u);
+            umax = umax.max(u);
+            vmin = vmin.min(v);
+            vmax = vmax.max(v);
+        }
+        let area = (umax - umin) * (vmax - vmin);
+        if area < best_area {
+            let (u, v) = ((umin + umax) / 2., (vmin + vmax) / 2.);
+            best = RotatedRect {
+                center: ((u * cos - v * sin) as f32, (u * sin + v * cos) as f32),
+                size: ((umax - umin) as f32, (vmax - vmin) as f32),
+                angle: angle as f32,
+            };
+            best_area = area;
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::{Image2DMut, ImageBuffer2D, Rect};
+
+    #[test]
+    fn test_region_moments() {
+        let mut img = ImageBuffer2D::<Luma<u8>>::new(20, 16);
+        assert_eq!(region_moments(&img).centroid(), None);
+
+        img.fill_rect(Rect::new(3, 5, 8, 5), &Luma::new([255]));
+        let m = region_moments(&img);
+        assert_eq!(m.m00, 40.);
+        assert_eq!(m.centroid(), Some((6.5, 7.)));
+    }
+
+    fn ellipse(w: u32, h: u32, a: f64, b: f64, angle: f64) -> ImageBuffer2D<Luma<u8>> {
+        let (cx, cy) = (f64::from(w) / 2., f64::from(h) / 2.);
+        let (sin, cos) = angle.sin_cos();
+        ImageBuffer2D::generate(w, h, |(x, y)| {
+            let (dx, dy) = (f64::from(x) - cx, f64::from(y) - cy);
+            let (u, v) = (dx * cos + dy * sin, dy * cos - dx * sin);
+            let inside = (u / a).powi(2) + (v / b).powi(2) <= 1.;
+            Luma::new([if inside { 255 } else { 0 }])
+        })
+    }
+
+    #[test]
+    fn test_orientation_eccentricity() {
+        let m = region_moments(&ellipse(64, 48, 25., 6., 0.));
+        assert!(m.orientation().abs() < 1e-3, "{}", m.orientation());
+        assert!(m.eccentricity() > 0.95, "{}", m.eccentricity());
+
+        let m = region_moments(&ellipse(64, 64, 25., 8., 0.5));
+        assert!((m.orientation() - 0.5).abs() < 0.02, "{}", m.orientation());
+
+        let m = region_moments(&ellipse(64, 64, 20., 20., 0.));
+        assert!(m.eccentricity() < 0.2, "{}", m.eccentricity());
+        assert_eq!(Moments::default().eccentricity(), 0.);
+    }
+
+    #[test]
+    fn test_find_contours() {
+        let mut img = ImageBuffer2D::<Luma<u8>>::new(20, 16);
+        assert!(find_contours(&img).is_empty());
+
+        img.fill_rect(Rect::new(4, 3, 6, 6), &Luma::new([255]));
+        let contours = find_contours(&img);
+        assert_eq!(contours.len(), 1);
+        let contour = &contours[0];
+        assert_eq!(contour.len(), 4 * 5);
+        assert_eq!(&contour[..3], &[(4, 3), (5, 3), (6, 3)]);
+        for &(x, y) in contour {
+            assert!(x == 4 || x == 9 || y == 3 || y == 8);
+        }
+
+        // A single pixel, a thin line and a shape with a hole.
+        img.put_pixel(15, 1, Luma::new([255]));
+        img.fill_rect(Rect::new(12, 5, 1, 3), &Luma::new([255]));
+        img.fill_rect(Rect::new(6, 5, 2, 2), &Luma::new([0]));
+        let contours = find_contours(&img);
+        assert_eq!(contours.len(), 3);
+        assert_eq!(contours[0], vec![(15, 1)]);
+        assert_eq!(contours[1].len(), 4 * 5);
+        assert_eq!(contours[2], vec![(12, 5), (12, 6), (12, 7), (12, 6)]);
+    }
+
+    #[test]
+    fn test_approx_poly_dp() {
+        let mut img = ImageBuffer2D::<Luma<u8>>::new(20, 16);
+        img.fill_rect(Rect::new(4, 3, 10, 8), &Luma::new([255]));
+        let contour = &find_contours(&img)[0];
+        assert_eq!(
+            approx_poly_dp(contour, 1.),
+            vec![(4, 3), (13, 3), (13, 10), (4, 10)]
+        );
+        assert_eq!(approx_poly_dp(contour, 0.).len(), 4);
+
+        // A small bump is kept or dropped depending on the tolerance.
+        img.put_pixel(8, 11, Luma::new([255]));
+        let contour = &find_contours(&img)[0];
+        assert_eq!(approx_poly_dp(contour, 2.).len(), 4);
+        assert!(approx_poly_dp(contour, 0.5).contains(&(8, 11)));
+        assert_eq!(approx_poly_dp(&[(1, 1), (2, 2)], 1.), vec![(1, 1), (2, 2)]);
+    }
+
+    #[test]
+    fn test_convex_hull() {
+        let mut points = vec![(0, 0), (10, 0), (10, 10), (0, 10), (5, 0), (10, 4)];
+        points.extend((1..10).flat_map(|x| (1..10).map(move |y| (x, y))));
+        points.push((0, 0));
+        assert_eq!(
+            convex_hull(&points),
+            vec![(0, 0), (10, 0), (10, 10), (0, 10)]
+        );
+
+        assert_eq!(
+            convex_hull(&[(3, -1), (-2, 4), (0, 0), (1, 1), (-2, 4)]),
+            vec![(-2, 4), (0, 0), (3, -1)]
+        );
+        assert_eq!(convex_hull(&[(1, 1), (1, 1)]), vec![(1, 1)]);
+        assert!(convex_hull(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_min_area_rect() {
+        // Points on the outline of a 60x40 rectangle rotated by 0.3 radians around (100, 80).
+        let (sin, cos) = 0.3f64.sin_cos();
+        let mut points = Vec::new();
+        for i in 0..=10 {
+            let t = f64::from(i) / 10.;
+            for &(u, v) in &[(t, 0.), (t, 1.), (0., t), (1., t)] {
+                let (u, v) = ((u - 0.5) * 60., (v - 0.5) * 40.);
+                points.push((
+                    (100. + u * cos - v * sin).round() as i64,
+                    (80. + u * sin + v * cos).round() as i64,
+                ));
+            }
+        }
+        let rect = min_area_rect(&points);
+        assert!((rect.angle - 0.3).abs() < 0.03, "{:?}", rect);
+        assert!((rect.center.0 - 100.).abs() < 1. && (rect.center.1 - 80.).abs() < 1.);
+        assert!((rect.size.0 - 60.).abs() < 1.5 && (rect.size.1 - 40.).abs() < 1.5);
+
+        let rect = min_area_rect(&[(2, 1), (6, 1), (6, 4), (2, 4), (3, 2)]);
+        assert_eq!(
+            rect,
+            RotatedRect {
+                center: (4., 2.5),
+                size: (4., 3.),
+                angle: 0.
+            }
+        );
+        assert_eq!(min_area_rect(&[(5, 7)]).center, (5., 7.));
+        assert_eq!(min_area_rect(&[]), RotatedRect::default());
+    }
+}